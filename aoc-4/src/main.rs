@@ -1,16 +1,31 @@
+use std::env;
 use std::fs;
 
 mod xmas_search;
 use xmas_search::x_mas_search;
 use xmas_search::xmas_search;
+use xmas_search::{find_x_mas_matches, find_xmas_matches, render_matches};
 
 fn main() {
     let words =
         fs::read_to_string("./data/words.txt").expect("Should have been able to read the file");
 
+    // `cargo run -- --render` prints the grids with the matches highlighted
+    let render = env::args().any(|arg| arg == "--render");
+
     let word_search_result = xmas_search(&words);
     println!("XMAS/SAMX found: {} times", word_search_result);
 
+    if render {
+        let matches = find_xmas_matches(&words);
+        println!("{}\n", render_matches(&words, &matches, true));
+    }
+
     let word_search_result = x_mas_search(&words);
     println!("MAS as X found: {} times", word_search_result);
+
+    if render {
+        let matches = find_x_mas_matches(&words);
+        println!("{}", render_matches(&words, &matches, true));
+    }
 }
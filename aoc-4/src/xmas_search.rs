@@ -1,5 +1,8 @@
 use regex::Regex;
 
+mod rendering;
+pub use rendering::{find_x_mas_matches, find_xmas_matches, render_matches};
+
 // ############################################################################################
 // ############################################################################################
 // # VARIANT 1                                                                                #
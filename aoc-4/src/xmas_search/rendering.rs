@@ -0,0 +1,254 @@
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+    DiagonalTopLeftToBottomRight,
+    DiagonalBottomLeftToTopRight,
+    Cross,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WordMatch {
+    pub direction: Direction,
+    // (x, y) positions of every letter belonging to the match
+    pub cells: Vec<(usize, usize)>,
+}
+
+const RESET: &str = "\x1b[0m";
+
+fn color_for_direction(direction: Direction) -> &'static str {
+    return match direction {
+        Direction::Horizontal => "\x1b[31m",
+        Direction::Vertical => "\x1b[32m",
+        Direction::DiagonalTopLeftToBottomRight => "\x1b[33m",
+        Direction::DiagonalBottomLeftToTopRight => "\x1b[34m",
+        Direction::Cross => "\x1b[35m",
+    };
+}
+
+fn to_grid(text: &str) -> Vec<Vec<char>> {
+    return text
+        .trim()
+        .split("\n")
+        .map(|line| line.trim().chars().collect())
+        .collect();
+}
+
+fn char_at(grid: &Vec<Vec<char>>, x: i64, y: i64) -> Option<char> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+
+    return grid
+        .get(y as usize)
+        .and_then(|line| line.get(x as usize))
+        .copied();
+}
+
+// collects the cells of `word` starting at (x, y) and walking along (dx, dy), if the word is found
+fn word_cells(
+    grid: &Vec<Vec<char>>,
+    word: &str,
+    (x, y): (i64, i64),
+    (dx, dy): (i64, i64),
+) -> Option<Vec<(usize, usize)>> {
+    let mut cells = vec![];
+
+    for (i, expected) in word.chars().enumerate() {
+        let cell_x = x + dx * i as i64;
+        let cell_y = y + dy * i as i64;
+
+        if char_at(grid, cell_x, cell_y) != Some(expected) {
+            return None;
+        }
+        cells.push((cell_x as usize, cell_y as usize));
+    }
+
+    return Some(cells);
+}
+
+// same matches as `xmas_search`, but with the positions of every letter
+pub fn find_xmas_matches(text: &str) -> Vec<WordMatch> {
+    let grid = to_grid(text);
+    let words = ["XMAS", "SAMX"];
+    let directions = [
+        (Direction::Horizontal, (1, 0)),
+        (Direction::Vertical, (0, 1)),
+        (Direction::DiagonalTopLeftToBottomRight, (1, 1)),
+        (Direction::DiagonalBottomLeftToTopRight, (1, -1)),
+    ];
+
+    let mut matches = vec![];
+
+    for (y, line) in grid.iter().enumerate() {
+        for x in 0..line.len() {
+            for (direction, step) in directions {
+                for word in words {
+                    if let Some(cells) = word_cells(&grid, word, (x as i64, y as i64), step) {
+                        matches.push(WordMatch { direction, cells });
+                    }
+                }
+            }
+        }
+    }
+
+    return matches;
+}
+
+// same matches as `x_mas_search`, but with the positions of every letter
+pub fn find_x_mas_matches(text: &str) -> Vec<WordMatch> {
+    let grid = to_grid(text);
+    let words = ["MAS", "SAM"];
+
+    let mut matches = vec![];
+
+    for (y, line) in grid.iter().enumerate() {
+        for x in 0..line.len() {
+            let (x, y) = (x as i64, y as i64);
+
+            let diagonal_1 = words
+                .iter()
+                .find_map(|word| word_cells(&grid, word, (x, y), (1, 1)));
+            let diagonal_2 = words
+                .iter()
+                .find_map(|word| word_cells(&grid, word, (x, y + 2), (1, -1)));
+
+            if let (Some(mut cells), Some(other_cells)) = (diagonal_1, diagonal_2) {
+                // both diagonals share the center `A`
+                cells.extend(
+                    other_cells
+                        .into_iter()
+                        .filter(|cell| *cell != (x as usize + 1, y as usize + 1)),
+                );
+                matches.push(WordMatch {
+                    direction: Direction::Cross,
+                    cells,
+                });
+            }
+        }
+    }
+
+    return matches;
+}
+
+// prints the grid with all letters not part of a match replaced by `.`.
+// With `colored` every match gets an ANSI color depending on its direction,
+// cells shared by several matches take the color of the last one.
+pub fn render_matches(text: &str, matches: &[WordMatch], colored: bool) -> String {
+    let grid = to_grid(text);
+    let mut highlights: Vec<Vec<Option<Direction>>> =
+        grid.iter().map(|line| vec![None; line.len()]).collect();
+
+    for word_match in matches {
+        for (x, y) in &word_match.cells {
+            highlights[*y][*x] = Some(word_match.direction);
+        }
+    }
+
+    let mut lines: Vec<String> = vec![];
+
+    for (y, line) in grid.iter().enumerate() {
+        let mut rendered = String::new();
+
+        for (x, ch) in line.iter().enumerate() {
+            match highlights[y][x] {
+                Some(direction) if colored => {
+                    rendered.push_str(color_for_direction(direction));
+                    rendered.push(*ch);
+                    rendered.push_str(RESET);
+                }
+                Some(_) => rendered.push(*ch),
+                None => rendered.push('.'),
+            }
+        }
+        lines.push(rendered);
+    }
+
+    return lines.join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_x_mas_matches, find_xmas_matches, render_matches, Direction, WordMatch};
+    use crate::xmas_search::{x_mas_search, xmas_search};
+
+    const EXAMPLE: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    #[test]
+    fn test_find_xmas_matches() {
+        let matches = find_xmas_matches(EXAMPLE);
+        assert_eq!(matches.len() as u32, xmas_search(EXAMPLE));
+
+        let matches = find_xmas_matches("..X.\n.M..\nA...\n....");
+        assert_eq!(matches, vec![]);
+
+        let matches = find_xmas_matches("...X\n..M.\n.A..\nS...");
+        assert_eq!(
+            matches,
+            vec![WordMatch {
+                direction: Direction::DiagonalBottomLeftToTopRight,
+                cells: vec![(0, 3), (1, 2), (2, 1), (3, 0)]
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_x_mas_matches() {
+        let matches = find_x_mas_matches(EXAMPLE);
+        assert_eq!(matches.len() as u32, x_mas_search(EXAMPLE));
+    }
+
+    #[test]
+    fn test_render_xmas_matches() {
+        let matches = find_xmas_matches(EXAMPLE);
+        assert_eq!(
+            render_matches(EXAMPLE, &matches, false),
+            "....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX"
+        );
+    }
+
+    #[test]
+    fn test_render_x_mas_matches() {
+        let matches = find_x_mas_matches(EXAMPLE);
+        assert_eq!(
+            render_matches(EXAMPLE, &matches, false),
+            ".M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+.........."
+        );
+    }
+
+    #[test]
+    fn test_render_colored() {
+        let matches = find_xmas_matches("XMAS\n....");
+        assert_eq!(
+            render_matches("XMAS\n....", &matches, true),
+            "\x1b[31mX\x1b[0m\x1b[31mM\x1b[0m\x1b[31mA\x1b[0m\x1b[31mS\x1b[0m\n...."
+        );
+    }
+}
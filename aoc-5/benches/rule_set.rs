@@ -0,0 +1,87 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs;
+use std::hint::black_box;
+
+use aoc_5::safety_protocoll_printing::{Page, Rule, RuleSet};
+
+// the validation as it was done before the rules got indexed, scanning all rules for every page
+fn check_update_by_scanning(update: &[Page], order_instructions: &[Rule]) -> bool {
+    return update.iter().all(|current| {
+        for (before, after) in order_instructions {
+            if !(current == before || current == after)
+                || !(update.contains(before) && update.contains(after))
+            {
+                continue;
+            }
+
+            let current_index = update.iter().position(|u| u == current).unwrap();
+            let before_index = update.iter().position(|u| u == before).unwrap();
+            let after_index = update.iter().position(|u| u == after).unwrap();
+
+            if (current == before && current_index > after_index)
+                || (current == after && current_index < before_index)
+            {
+                return false;
+            }
+        }
+        return true;
+    });
+}
+
+fn load_plan() -> (Vec<Rule>, Vec<Vec<Page>>) {
+    let update_plan = fs::read_to_string("./data/updates_plan.txt")
+        .expect("Should have been able to read the file");
+
+    let (rules, updates) = update_plan.trim().split_once("\n\n").unwrap();
+
+    let rules = rules
+        .lines()
+        .map(|rule| {
+            let (before, after) = rule.trim().split_once("|").unwrap();
+            (before.to_string(), after.to_string())
+        })
+        .collect();
+
+    let updates = updates
+        .lines()
+        .map(|update| {
+            update
+                .trim()
+                .split(",")
+                .map(|page| page.to_string())
+                .collect()
+        })
+        .collect();
+
+    return (rules, updates);
+}
+
+fn validate_updates(c: &mut Criterion) {
+    let (rules, updates) = load_plan();
+    let rule_set = RuleSet::new(&rules);
+
+    let mut group = c.benchmark_group("validate updates");
+
+    group.bench_function("scanning all rules", |b| {
+        b.iter(|| {
+            updates
+                .iter()
+                .filter(|update| check_update_by_scanning(black_box(update), &rules))
+                .count()
+        })
+    });
+
+    group.bench_function("indexed rule set", |b| {
+        b.iter(|| {
+            updates
+                .iter()
+                .filter(|update| rule_set.is_ordered(black_box(update)))
+                .count()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, validate_updates);
+criterion_main!(benches);
@@ -1,20 +1,76 @@
+use std::env;
 use std::fs;
-mod safety_protocoll_printing;
-use safety_protocoll_printing::{repair_and_summarize_invalid_updates, summarize_valid_updates};
+
+use aoc_5::safety_protocoll_printing::{
+    explain_invalid_updates, parse_page, repair_and_summarize_invalid_updates,
+    summarize_valid_updates, Page, PrintPlan, Rule,
+};
+
+fn parse_rule(rule: &str) -> Result<Rule, String> {
+    let (before, after) = rule
+        .split_once("|")
+        .ok_or(format!("rule '{}' has to be given as before|after", rule))?;
+    return Ok((parse_page(before)?, parse_page(after)?));
+}
+
+fn parse_update(update: &str) -> Result<Vec<Page>, String> {
+    return update.split(",").map(parse_page).collect();
+}
+
+// `cargo run -- --without-rule 97|75 --with-update 75,97,47` shows which updates change
+// their validity or repaired order by editing the plan
+fn explore_edits(update_plan: &str, args: &Vec<String>) {
+    let mut plan = PrintPlan::parse(update_plan).unwrap();
+
+    for edit in args.windows(2) {
+        let changes = match edit[0].as_str() {
+            "--with-rule" => parse_rule(&edit[1]).map(|rule| plan.add_rule(rule)),
+            "--without-rule" => parse_rule(&edit[1]).map(|rule| plan.remove_rule(&rule)),
+            "--with-update" => parse_update(&edit[1]).map(|update| vec![plan.add_update(update)]),
+            "--without-update" => edit[1]
+                .parse()
+                .map_err(|_| format!("updates are removed by their index, not '{}'", edit[1]))
+                .map(|index| plan.remove_update(index).into_iter().collect()),
+            _ => continue,
+        };
+
+        println!("\n{} {}:", edit[0], edit[1]);
+        let changes = match changes {
+            Ok(changes) => changes,
+            Err(error) => {
+                println!("  {}", error);
+                continue;
+            }
+        };
+        for change in changes {
+            println!("  {}", change);
+        }
+    }
+}
 
 fn main() {
     let update_plan = fs::read_to_string("./data/updates_plan.txt")
         .expect("Should have been able to read the file");
 
-    let result = summarize_valid_updates(&update_plan);
+    let result = summarize_valid_updates(&update_plan).unwrap();
     println!(
         "sum of middlepage numbers for correctly ordered pages is: {}",
         result
     );
 
-    let result = repair_and_summarize_invalid_updates(&update_plan);
+    let result = repair_and_summarize_invalid_updates(&update_plan).unwrap();
     println!(
         "sum of middlepage numbers for incorrectly ordered pages is: {}",
         result
     );
+
+    // `cargo run -- --explain` lists the broken rules and required moves for every invalid update
+    if env::args().any(|arg| arg == "--explain") {
+        for explanation in explain_invalid_updates(&update_plan).unwrap() {
+            println!("\n{}", explanation);
+        }
+    }
+
+    let args: Vec<String> = env::args().collect();
+    explore_edits(&update_plan, &args);
 }
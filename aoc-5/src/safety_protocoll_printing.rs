@@ -1,72 +1,85 @@
-use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+mod explanation;
+mod print_plan;
+mod rule_set;
+pub use explanation::explain_invalid_updates;
+pub use print_plan::PrintPlan;
+pub use rule_set::{Page, Rule, RuleSet};
+
+fn is_comment(line: &str) -> bool {
+    return line.trim().starts_with("#");
+}
 
-fn parse_instruction(instruction: &str) -> (u32, u32) {
-    let instruction: Vec<&str> = instruction.trim().split("|").collect();
-    let instruction: Vec<u32> = instruction
-        .iter()
-        .map(|i| i.parse::<u32>().unwrap())
-        .collect();
+pub fn parse_page(page: &str) -> Result<Page, String> {
+    let page = page.trim();
 
-    if instruction.len() > 2 {
-        panic!("order instruction contains more then two pages")
-    };
+    if page.is_empty()
+        || !page
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(format!("invalid page identifier '{}'", page));
+    }
+    return Ok(page.to_string());
+}
+
+// a chain `a|b|c` is short for the rules `a|b` and `b|c`
+fn parse_instruction(instruction: &str) -> Result<Vec<Rule>, String> {
+    let pages = instruction
+        .trim()
+        .split("|")
+        .map(parse_page)
+        .collect::<Result<Vec<Page>, String>>()?;
+
+    if pages.len() < 2 {
+        return Err(format!(
+            "order instruction '{}' contains less than two pages",
+            instruction.trim()
+        ));
+    }
 
-    return (instruction[0], instruction[1]);
+    return Ok(pages
+        .windows(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect());
 }
 
-fn parse_sorting_order(order_instructions: &str) -> Vec<(u32, u32)> {
-    let instructions: Vec<&str> = order_instructions.split("\n").collect();
+fn parse_sorting_order(order_instructions: &str) -> Result<Vec<Rule>, String> {
+    let mut rules: Vec<Rule> = vec![];
 
-    return instructions
-        .iter()
-        .map(|i| parse_instruction(i))
-        .collect::<Vec<(u32, u32)>>();
+    for instruction in order_instructions.split("\n") {
+        if is_comment(instruction) {
+            continue;
+        }
+        rules.extend(parse_instruction(instruction)?);
+    }
+
+    return Ok(rules);
 }
 
-fn parse_updates(updates: &str) -> Vec<Vec<u32>> {
+fn parse_updates(updates: &str) -> Result<Vec<Vec<Page>>, String> {
     let updates: Vec<&str> = updates.split("\n").collect();
-    let mut result: Vec<Vec<u32>> = vec![];
+    let mut result: Vec<Vec<Page>> = vec![];
     for update in updates {
-        let parsed_update: Vec<u32> = update
+        if is_comment(update) {
+            continue;
+        }
+
+        let parsed_update = update
             .trim()
             .split(",")
-            .into_iter()
-            .map(|u| u.parse::<u32>().unwrap())
-            .collect();
+            .map(parse_page)
+            .collect::<Result<Vec<Page>, String>>()?;
 
         result.push(parsed_update);
     }
-    return result;
+    return Ok(result);
 }
 
-fn check_update_for_order(
-    update: &Vec<u32>,
-    order_instructions: &Vec<(u32, u32)>,
-) -> Option<Vec<u32>> {
-    let mut res = true;
-    if update.iter().all(|current| {
-        for (before, after) in order_instructions {
-            if !(current == before || current == after) {
-                // current instruction does not apply on that element
-                continue;
-            } else if !(update.contains(before) && update.contains(after)) {
-                // current instruction has one element, not even in this update, so it does not apply to it.
-                continue;
-            }
-
-            // because of the above implemented checks, we can safely unwrap the indexes here
-            let current_index = update.iter().position(|u| u == current).unwrap();
-            let before_index = update.iter().position(|u| u == before).unwrap();
-            let after_index = update.iter().position(|u| u == after).unwrap();
-
-            if current == before && current_index > after_index {
-                res = false
-            } else if current == after && current_index < before_index {
-                res = false
-            }
-        }
-        return res;
-    }) {
+fn check_update_for_order(update: &Vec<Page>, rule_set: &RuleSet) -> Option<Vec<Page>> {
+    if rule_set.is_ordered(update) {
         return Some(update.clone());
     }
     return None;
@@ -74,14 +87,14 @@ fn check_update_for_order(
 
 // returns only valid updates
 fn get_updates(
-    updates: &Vec<Vec<u32>>,
-    order_instructions: &Vec<(u32, u32)>,
+    updates: &Vec<Vec<Page>>,
+    rule_set: &RuleSet,
     valid_updates: bool,
-) -> Vec<Vec<u32>> {
-    let mut results: Vec<Vec<u32>> = vec![];
+) -> Vec<Vec<Page>> {
+    let mut results: Vec<Vec<Page>> = vec![];
 
     for update in updates {
-        if let Some(valid_update) = check_update_for_order(&update, &order_instructions) {
+        if let Some(valid_update) = check_update_for_order(&update, &rule_set) {
             if valid_updates {
                 results.push(valid_update);
             }
@@ -95,131 +108,233 @@ fn get_updates(
     return results;
 }
 
-fn get_valid_updates(
-    updates: &Vec<Vec<u32>>,
-    order_instructions: &Vec<(u32, u32)>,
-) -> Vec<Vec<u32>> {
-    return get_updates(updates, order_instructions, true);
+fn get_valid_updates(updates: &Vec<Vec<Page>>, rule_set: &RuleSet) -> Vec<Vec<Page>> {
+    return get_updates(updates, rule_set, true);
 }
 
-fn get_invalid_updates(
-    updates: &Vec<Vec<u32>>,
-    order_instructions: &Vec<(u32, u32)>,
-) -> Vec<Vec<u32>> {
-    return get_updates(updates, order_instructions, false);
+fn get_invalid_updates(updates: &Vec<Vec<Page>>, rule_set: &RuleSet) -> Vec<Vec<Page>> {
+    return get_updates(updates, rule_set, false);
 }
 
-fn middle_page(update: &Vec<u32>) -> u32 {
-    return update[update.len() / 2]; // zero based
+fn middle_page(update: &Vec<Page>) -> &Page {
+    return &update[update.len() / 2]; // zero based
 }
 
-fn summarize_updates(updates: &Vec<Vec<u32>>) -> u32 {
+// summing up only works for numeric page identifiers
+fn summarize_updates(updates: &Vec<Vec<Page>>) -> Result<u32, String> {
     let mut sum = 0;
 
     for update in updates {
-        sum += middle_page(&update);
+        let page = middle_page(&update);
+        sum += page
+            .parse::<u32>()
+            .map_err(|_| format!("middle page '{}' is not a number", page))?;
     }
 
-    return sum;
+    return Ok(sum);
 }
 
-pub fn summarize_valid_updates(update_plan: &str) -> u32 {
+pub fn summarize_valid_updates(update_plan: &str) -> Result<u32, String> {
     let updates = update_plan.trim();
 
     if let [order_instructions, updates] = updates.split("\n\n").collect::<Vec<&str>>().as_slice() {
-        let updates = parse_updates(&updates);
-        let order_instructions = parse_sorting_order(&order_instructions);
-        let valid_updates = get_valid_updates(&updates, &order_instructions);
+        let updates = parse_updates(&updates)?;
+        let rule_set = RuleSet::new(&parse_sorting_order(&order_instructions)?);
+        let valid_updates = get_valid_updates(&updates, &rule_set);
         return summarize_updates(&valid_updates);
     };
 
-    return 0;
+    return Ok(0);
 }
 
-fn repair_update(update: &mut Vec<u32>, instructions: &Vec<(u32, u32)>) {
-    update.sort_by(|a, b| {
-        for (before, after) in instructions {
-            if before == a && after == b {
-                return Ordering::Less;
-            } else if before == b && after == a {
-                return Ordering::Greater;
+// Sorts the update topologically along the rules which apply to its pages. Pages
+// which are not ordered relative to each other keep their original order.
+fn repair_update(update: &mut Vec<Page>, rule_set: &RuleSet) -> Result<(), String> {
+    let mut successors: Vec<Vec<usize>> = vec![vec![]; update.len()];
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]; update.len()];
+
+    for before in 0..update.len() {
+        for after in 0..update.len() {
+            if rule_set.must_precede(&update[before], &update[after]) {
+                successors[before].push(after);
+                predecessors[after].push(before);
+            }
+        }
+    }
+
+    let mut in_degree: Vec<usize> = predecessors.iter().map(|p| p.len()).collect();
+    let mut ready: BinaryHeap<Reverse<usize>> = in_degree
+        .iter()
+        .enumerate()
+        .filter(|(_, degree)| **degree == 0)
+        .map(|(index, _)| Reverse(index))
+        .collect();
+
+    let mut sorted: Vec<Page> = Vec::with_capacity(update.len());
+
+    while let Some(Reverse(index)) = ready.pop() {
+        sorted.push(update[index].clone());
+
+        for successor in &successors[index] {
+            in_degree[*successor] -= 1;
+            if in_degree[*successor] == 0 {
+                ready.push(Reverse(*successor));
             }
         }
-        return Ordering::Equal;
-    });
+    }
+
+    if sorted.len() < update.len() {
+        let cycle = find_cycle(&in_degree, &predecessors);
+        let cycle: Vec<String> = cycle.iter().map(|i| update[*i].clone()).collect();
+        return Err(format!(
+            "rules contain a cycle between pages {}",
+            cycle.join(" -> ")
+        ));
+    }
+
+    *update = sorted;
+    return Ok(());
+}
+
+// Every page left with an in-degree after the topological sort has a predecessor which
+// is left as well, so walking backwards along those will eventually revisit a page.
+fn find_cycle(in_degree: &Vec<usize>, predecessors: &Vec<Vec<usize>>) -> Vec<usize> {
+    let mut current = in_degree.iter().position(|degree| *degree > 0).unwrap();
+    let mut walked: Vec<usize> = vec![];
+
+    while !walked.contains(&current) {
+        walked.push(current);
+        current = *predecessors[current]
+            .iter()
+            .find(|p| in_degree[**p] > 0)
+            .unwrap();
+    }
+
+    let start = walked.iter().position(|i| *i == current).unwrap();
+    let mut cycle: Vec<usize> = walked[start..].to_vec();
+    cycle.push(current);
+    cycle.reverse();
+
+    return cycle;
 }
 
-fn repair_updates(updates: &mut Vec<Vec<u32>>, instructions: &Vec<(u32, u32)>) {
+fn repair_updates(updates: &mut Vec<Vec<Page>>, rule_set: &RuleSet) -> Result<(), String> {
     for update in updates {
-        repair_update(update, instructions);
+        repair_update(update, rule_set)?;
     }
+    return Ok(());
 }
 
-pub fn repair_and_summarize_invalid_updates(update_plan: &str) -> u32 {
+pub fn repair_and_summarize_invalid_updates(update_plan: &str) -> Result<u32, String> {
     let updates = update_plan.trim();
 
     if let [order_instructions, updates] = updates.split("\n\n").collect::<Vec<&str>>().as_slice() {
-        let updates = parse_updates(&updates);
-        let order_instructions = parse_sorting_order(&order_instructions);
-        let mut invalid_updates = get_invalid_updates(&updates, &order_instructions);
-        repair_updates(&mut invalid_updates, &order_instructions);
+        let updates = parse_updates(&updates)?;
+        let rule_set = RuleSet::new(&parse_sorting_order(&order_instructions)?);
+        let mut invalid_updates = get_invalid_updates(&updates, &rule_set);
+        repair_updates(&mut invalid_updates, &rule_set)?;
         return summarize_updates(&invalid_updates);
     };
 
-    return 0;
+    return Ok(0);
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        check_update_for_order, get_valid_updates, parse_sorting_order,
+        check_update_for_order, get_valid_updates, parse_sorting_order, parse_updates,
         repair_and_summarize_invalid_updates, repair_update, summarize_updates,
-        summarize_valid_updates,
+        summarize_valid_updates, Page, Rule, RuleSet,
     };
 
+    // the example pages are all numeric, so they are written as numbers for brevity
+    pub(crate) fn pages(pages: &[u32]) -> Vec<Page> {
+        return pages.iter().map(|page| page.to_string()).collect();
+    }
+
+    pub(crate) fn rules(rules: &[(u32, u32)]) -> Vec<Rule> {
+        return rules
+            .iter()
+            .map(|(before, after)| (before.to_string(), after.to_string()))
+            .collect();
+    }
+
     #[test]
     fn test_repair_update() {
-        let example_order = vec![(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)];
-        let mut example_updates = vec![75, 47, 61, 97, 53, 29];
+        let example_order =
+            RuleSet::new(&rules(&[(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)]));
+        let mut example_updates = pages(&[75, 47, 61, 97, 53, 29]);
 
-        repair_update(&mut example_updates, &example_order);
-        assert_eq!(example_updates, vec![75, 97, 47, 61, 53, 29]);
+        repair_update(&mut example_updates, &example_order).unwrap();
+        assert_eq!(example_updates, pages(&[75, 97, 47, 61, 53, 29]));
+    }
+
+    #[test]
+    fn test_repair_update_with_unrelated_pages() {
+        // 5 is unrelated to both other pages, a comparator based sort would leave this untouched
+        let example_order = RuleSet::new(&rules(&[(1, 3)]));
+        let mut example_updates = pages(&[3, 5, 1]);
+
+        repair_update(&mut example_updates, &example_order).unwrap();
+        assert_eq!(example_updates, pages(&[5, 1, 3]));
+        assert!(check_update_for_order(&example_updates, &example_order).is_some());
+    }
+
+    #[test]
+    fn test_repair_update_with_cycle() {
+        let example_order = RuleSet::new(&rules(&[(47, 53), (53, 29), (29, 47), (75, 47)]));
+        let mut example_updates = pages(&[75, 47, 53, 29]);
+
+        let result = repair_update(&mut example_updates, &example_order);
+        assert_eq!(
+            result,
+            Err("rules contain a cycle between pages 47 -> 53 -> 29 -> 47".to_string())
+        );
+        assert_eq!(example_updates, pages(&[75, 47, 53, 29]));
     }
 
     #[test]
     fn test_summarize_updates() {
         let example_updates = vec![
-            vec![75, 47, 61, 53, 29],
-            vec![97, 61, 53, 29, 13],
-            vec![75, 29, 13],
+            pages(&[75, 47, 61, 53, 29]),
+            pages(&[97, 61, 53, 29, 13]),
+            pages(&[75, 29, 13]),
         ];
 
-        let result = summarize_updates(&example_updates);
+        let result = summarize_updates(&example_updates).unwrap();
         assert_eq!(result, 143);
+
+        let result = summarize_updates(&vec![vec!["intro".to_string()]]);
+        assert_eq!(
+            result,
+            Err("middle page 'intro' is not a number".to_string())
+        );
     }
 
     #[test]
     fn test_check_update_for_order() {
-        let example_order = vec![(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)];
-        let example_update: Vec<u32> = vec![75, 47, 61, 53, 29];
+        let example_order =
+            RuleSet::new(&rules(&[(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)]));
+        let example_update: Vec<Page> = pages(&[75, 47, 61, 53, 29]);
 
         let result = check_update_for_order(&example_update, &example_order);
-        assert_eq!(result.unwrap(), vec![75, 47, 61, 53, 29]);
+        assert_eq!(result.unwrap(), pages(&[75, 47, 61, 53, 29]));
     }
 
     #[test]
     fn test_get_valid_updates() {
-        let example_order = vec![(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)];
+        let example_order =
+            RuleSet::new(&rules(&[(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)]));
         let example_updates = vec![
-            vec![75, 47, 61, 53, 29],
-            vec![97, 61, 53, 29, 13],
-            vec![75, 29, 13, 97],
+            pages(&[75, 47, 61, 53, 29]),
+            pages(&[97, 61, 53, 29, 13]),
+            pages(&[75, 29, 13, 97]),
         ];
 
         let result = get_valid_updates(&example_updates, &example_order);
         assert_eq!(
             result,
-            vec![vec![75, 47, 61, 53, 29], vec![97, 61, 53, 29, 13]]
+            vec![pages(&[75, 47, 61, 53, 29]), pages(&[97, 61, 53, 29, 13])]
         );
     }
 
@@ -230,10 +345,48 @@ mod tests {
 97|61
 97|47
 75|29";
-        let result = parse_sorting_order(&example_sorting_order);
+        let result = parse_sorting_order(&example_sorting_order).unwrap();
+        assert_eq!(
+            result,
+            rules(&[(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)])
+        );
+    }
+
+    #[test]
+    fn test_parse_sorting_order_with_chains_and_comments() {
+        let example_sorting_order = "# front matter
+intro|chapter-1|chapter_2
+# appendix goes last
+chapter_2|appendix";
+        let result = parse_sorting_order(&example_sorting_order).unwrap();
+        assert_eq!(
+            result,
+            vec![
+                ("intro".to_string(), "chapter-1".to_string()),
+                ("chapter-1".to_string(), "chapter_2".to_string()),
+                ("chapter_2".to_string(), "appendix".to_string())
+            ]
+        );
+
+        let result = parse_sorting_order("47|53\n47");
+        assert_eq!(
+            result,
+            Err("order instruction '47' contains less than two pages".to_string())
+        );
+
+        let result = parse_sorting_order("47|5 3");
+        assert_eq!(result, Err("invalid page identifier '5 3'".to_string()));
+    }
+
+    #[test]
+    fn test_parse_updates() {
+        let result = parse_updates("75,47,61\n# skipped\nintro,appendix").unwrap();
         assert_eq!(
             result,
-            vec![(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)]
+            vec![
+                pages(&[75, 47, 61]),
+                vec!["intro".to_string(), "appendix".to_string()]
+            ]
         );
     }
 
@@ -268,7 +421,7 @@ mod tests {
 61,13,29
 97,13,75,29,47";
 
-        let result = summarize_valid_updates(&example_plan);
+        let result = summarize_valid_updates(&example_plan).unwrap();
 
         assert_eq!(result, 143);
     }
@@ -304,7 +457,7 @@ mod tests {
 61,13,29
 97,13,75,29,47";
 
-        let result = repair_and_summarize_invalid_updates(&example_plan);
+        let result = repair_and_summarize_invalid_updates(&example_plan).unwrap();
 
         assert_eq!(result, 123);
     }
@@ -0,0 +1,260 @@
+use std::fmt;
+
+use super::rule_set::{Page, RuleSet, Violation};
+use super::{get_invalid_updates, parse_sorting_order, parse_updates, repair_update};
+
+// moving a single page from one index to another, indexes are taken from the
+// update as it is right before the move is applied
+#[derive(Debug, PartialEq, Clone)]
+pub struct Move {
+    pub page: Page,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "move page {} from index {} to {}",
+            self.page, self.from, self.to
+        );
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Explanation {
+    pub update: Vec<Page>,
+    pub violations: Vec<Violation>,
+    pub repaired: Vec<Page>,
+    pub moves: Vec<Move>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "update {} is invalid:", self.update.join(","))?;
+        for violation in &self.violations {
+            writeln!(f, "  - {}", violation)?;
+        }
+
+        write!(f, "repaired to {} by:", self.repaired.join(","))?;
+        for page_move in &self.moves {
+            write!(f, "\n  - {}", page_move)?;
+        }
+        return Ok(());
+    }
+}
+
+// indexes of the longest subsequence of `sequence` which is strictly increasing
+fn longest_increasing_subsequence(sequence: &Vec<usize>) -> Vec<usize> {
+    // tails[l] is the index of the smallest tail of all increasing subsequences of length l + 1
+    let mut tails: Vec<usize> = vec![];
+    let mut previous: Vec<Option<usize>> = vec![None; sequence.len()];
+
+    for (index, value) in sequence.iter().enumerate() {
+        let length = tails.partition_point(|tail| sequence[*tail] < *value);
+
+        if length > 0 {
+            previous[index] = Some(tails[length - 1]);
+        }
+
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+
+    let mut subsequence: Vec<usize> = vec![];
+    let mut current = tails.last().copied();
+
+    while let Some(index) = current {
+        subsequence.push(index);
+        current = previous[index];
+    }
+    subsequence.reverse();
+
+    return subsequence;
+}
+
+// The pages which already are in the right relative order form the longest increasing
+// subsequence of their target positions. All other pages have to move once, each of them
+// right behind the page it follows in the repaired update.
+fn minimal_moves(update: &Vec<Page>, repaired: &Vec<Page>) -> Vec<Move> {
+    let target_positions: Vec<usize> = update
+        .iter()
+        .map(|page| repaired.iter().position(|p| p == page).unwrap())
+        .collect();
+
+    let kept: Vec<Page> = longest_increasing_subsequence(&target_positions)
+        .iter()
+        .map(|index| update[*index].clone())
+        .collect();
+
+    let mut current = update.clone();
+    let mut moves: Vec<Move> = vec![];
+
+    for (target, page) in repaired.iter().enumerate() {
+        if kept.contains(page) {
+            continue;
+        }
+
+        let from = current.iter().position(|p| p == page).unwrap();
+        current.remove(from);
+
+        let to = if target == 0 {
+            0
+        } else {
+            current
+                .iter()
+                .position(|p| *p == repaired[target - 1])
+                .unwrap()
+                + 1
+        };
+        current.insert(to, page.clone());
+
+        moves.push(Move {
+            page: page.clone(),
+            from,
+            to,
+        });
+    }
+
+    return moves;
+}
+
+fn explain_update(update: &Vec<Page>, rule_set: &RuleSet) -> Result<Explanation, String> {
+    let mut repaired = update.clone();
+    repair_update(&mut repaired, rule_set)?;
+
+    return Ok(Explanation {
+        update: update.clone(),
+        violations: rule_set.violations(update),
+        moves: minimal_moves(update, &repaired),
+        repaired,
+    });
+}
+
+pub fn explain_invalid_updates(update_plan: &str) -> Result<Vec<Explanation>, String> {
+    let updates = update_plan.trim();
+
+    if let [order_instructions, updates] = updates.split("\n\n").collect::<Vec<&str>>().as_slice() {
+        let updates = parse_updates(&updates)?;
+        let rule_set = RuleSet::new(&parse_sorting_order(&order_instructions)?);
+
+        return get_invalid_updates(&updates, &rule_set)
+            .iter()
+            .map(|update| explain_update(update, &rule_set))
+            .collect();
+    };
+
+    return Ok(vec![]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        explain_invalid_updates, explain_update, longest_increasing_subsequence, minimal_moves,
+        Move,
+    };
+    use crate::safety_protocoll_printing::tests::{pages, rules};
+    use crate::safety_protocoll_printing::{Page, RuleSet};
+
+    #[test]
+    fn test_longest_increasing_subsequence() {
+        assert_eq!(
+            longest_increasing_subsequence(&vec![0, 2, 3, 1, 4]),
+            vec![0, 1, 2, 4]
+        );
+        assert_eq!(longest_increasing_subsequence(&vec![2, 1, 0]), vec![2]);
+        assert_eq!(longest_increasing_subsequence(&vec![]), vec![]);
+    }
+
+    #[test]
+    fn test_minimal_moves() {
+        let moves = minimal_moves(
+            &pages(&[75, 47, 61, 97, 53, 29]),
+            &pages(&[75, 97, 47, 61, 53, 29]),
+        );
+        assert_eq!(
+            moves,
+            vec![Move {
+                page: "97".to_string(),
+                from: 3,
+                to: 1
+            }]
+        );
+
+        let moves = minimal_moves(&pages(&[3, 2, 1]), &pages(&[1, 2, 3]));
+        assert_eq!(moves.len(), 2);
+
+        let mut update = pages(&[3, 2, 1]);
+        for Move { from, to, .. } in moves {
+            let page = update.remove(from);
+            update.insert(to, page);
+        }
+        assert_eq!(update, pages(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn test_explain_update() {
+        let rule_set = RuleSet::new(&rules(&[(97, 13), (97, 47), (75, 29), (29, 13), (47, 13)]));
+
+        let explanation = explain_update(&pages(&[97, 13, 75, 29, 47]), &rule_set).unwrap();
+        assert_eq!(explanation.repaired, pages(&[97, 75, 29, 47, 13]));
+        assert_eq!(
+            explanation.to_string(),
+            "update 97,13,75,29,47 is invalid:
+  - page 29 must come before 13 but is at index 3 vs 1
+  - page 47 must come before 13 but is at index 4 vs 1
+repaired to 97,75,29,47,13 by:
+  - move page 13 from index 1 to 4"
+        );
+    }
+
+    #[test]
+    fn test_explain_invalid_updates() {
+        let example_plan = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+        let explanations = explain_invalid_updates(example_plan).unwrap();
+        let repaired: Vec<Vec<Page>> = explanations.iter().map(|e| e.repaired.clone()).collect();
+        assert_eq!(
+            repaired,
+            vec![
+                pages(&[97, 75, 47, 61, 53]),
+                pages(&[61, 29, 13]),
+                pages(&[97, 75, 47, 29, 13])
+            ]
+        );
+
+        let moves: Vec<usize> = explanations.iter().map(|e| e.moves.len()).collect();
+        assert_eq!(moves, vec![1, 1, 2]);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+use super::rule_set::{Page, Rule, RuleSet};
+use super::{parse_sorting_order, parse_updates, repair_update};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Status {
+    Valid,
+    Repaired(Vec<Page>),
+    // the rules applying to the update contain a cycle
+    Unrepairable(String),
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Status::Valid => write!(f, "valid"),
+            Status::Repaired(repaired) => write!(f, "invalid, repaired to {}", repaired.join(",")),
+            Status::Unrepairable(reason) => write!(f, "invalid, {}", reason),
+        };
+    }
+}
+
+// an update whose status differs after editing the plan, `None` means the update
+// was not part of the plan before or is not part of it anymore
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub id: usize,
+    pub update: Vec<Page>,
+    pub before: Option<Status>,
+    pub after: Option<Status>,
+}
+
+fn describe(status: &Option<Status>) -> String {
+    return match status {
+        Some(status) => status.to_string(),
+        None => "not planned".to_string(),
+    };
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "update {} ({}): {} -> {}",
+            self.id,
+            self.update.join(","),
+            describe(&self.before),
+            describe(&self.after)
+        );
+    }
+}
+
+fn status_of(update: &Vec<Page>, rule_set: &RuleSet) -> Status {
+    if rule_set.is_ordered(update) {
+        return Status::Valid;
+    }
+
+    let mut repaired = update.clone();
+    return match repair_update(&mut repaired, rule_set) {
+        Ok(()) => Status::Repaired(repaired),
+        Err(reason) => Status::Unrepairable(reason),
+    };
+}
+
+// A loaded update plan which can be edited. Every edit only re-validates the updates
+// the edited rule or update applies to and reports those whose status changed.
+#[derive(Debug)]
+pub struct PrintPlan {
+    rule_set: RuleSet,
+    updates: BTreeMap<usize, (Vec<Page>, Status)>,
+    // ids of all updates containing a page
+    update_ids_by_page: HashMap<Page, BTreeSet<usize>>,
+    next_id: usize,
+}
+
+impl PrintPlan {
+    pub fn parse(update_plan: &str) -> Result<PrintPlan, String> {
+        let Some((order_instructions, updates)) = update_plan.trim().split_once("\n\n") else {
+            return Err(
+                "update plan has to separate rules and updates by an empty line".to_string(),
+            );
+        };
+
+        let mut plan = PrintPlan {
+            rule_set: RuleSet::new(&parse_sorting_order(order_instructions)?),
+            updates: BTreeMap::new(),
+            update_ids_by_page: HashMap::new(),
+            next_id: 0,
+        };
+
+        for update in parse_updates(updates)? {
+            plan.add_update(update);
+        }
+
+        return Ok(plan);
+    }
+
+    fn revalidate(&mut self, (before, after): &Rule) -> Vec<Change> {
+        let (Some(with_before), Some(with_after)) = (
+            self.update_ids_by_page.get(before),
+            self.update_ids_by_page.get(after),
+        ) else {
+            return vec![];
+        };
+
+        let affected: Vec<usize> = with_before.intersection(with_after).copied().collect();
+        let mut changes: Vec<Change> = vec![];
+
+        for id in affected {
+            let (update, status) = self.updates.get_mut(&id).unwrap();
+            let new_status = status_of(update, &self.rule_set);
+
+            if new_status != *status {
+                changes.push(Change {
+                    id,
+                    update: update.clone(),
+                    before: Some(status.clone()),
+                    after: Some(new_status.clone()),
+                });
+                *status = new_status;
+            }
+        }
+
+        return changes;
+    }
+
+    pub fn add_rule(&mut self, rule: Rule) -> Vec<Change> {
+        self.rule_set.insert(rule.clone());
+        return self.revalidate(&rule);
+    }
+
+    pub fn remove_rule(&mut self, rule: &Rule) -> Vec<Change> {
+        if !self.rule_set.remove(rule) {
+            return vec![];
+        }
+        return self.revalidate(rule);
+    }
+
+    pub fn add_update(&mut self, update: Vec<Page>) -> Change {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        for page in &update {
+            self.update_ids_by_page
+                .entry(page.clone())
+                .or_default()
+                .insert(id);
+        }
+
+        let status = status_of(&update, &self.rule_set);
+        self.updates.insert(id, (update.clone(), status.clone()));
+
+        return Change {
+            id,
+            update,
+            before: None,
+            after: Some(status),
+        };
+    }
+
+    pub fn remove_update(&mut self, id: usize) -> Option<Change> {
+        let (update, status) = self.updates.remove(&id)?;
+
+        for page in &update {
+            if let Some(ids) = self.update_ids_by_page.get_mut(page) {
+                ids.remove(&id);
+            }
+        }
+
+        return Some(Change {
+            id,
+            update,
+            before: Some(status),
+            after: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, PrintPlan, Status};
+    use crate::safety_protocoll_printing::tests::pages;
+
+    const EXAMPLE_PLAN: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+    fn rule(before: &str, after: &str) -> (String, String) {
+        return (before.to_string(), after.to_string());
+    }
+
+    fn status(plan: &PrintPlan, id: usize) -> Option<&Status> {
+        return plan.updates.get(&id).map(|(_, status)| status);
+    }
+
+    #[test]
+    fn test_parse() {
+        let plan = PrintPlan::parse(EXAMPLE_PLAN).unwrap();
+
+        assert_eq!(status(&plan, 0), Some(&Status::Valid));
+        assert_eq!(
+            status(&plan, 3),
+            Some(&Status::Repaired(pages(&[97, 75, 47, 61, 53])))
+        );
+        assert_eq!(status(&plan, 6), None);
+
+        assert!(PrintPlan::parse("47|53").is_err());
+    }
+
+    #[test]
+    fn test_remove_and_add_rule() {
+        let mut plan = PrintPlan::parse(EXAMPLE_PLAN).unwrap();
+
+        let changes = plan.remove_rule(&rule("97", "75"));
+        assert_eq!(
+            changes,
+            vec![Change {
+                id: 3,
+                update: pages(&[75, 97, 47, 61, 53]),
+                before: Some(Status::Repaired(pages(&[97, 75, 47, 61, 53]))),
+                after: Some(Status::Valid)
+            }]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "update 3 (75,97,47,61,53): invalid, repaired to 97,75,47,61,53 -> valid"
+        );
+
+        // nothing left to remove
+        assert_eq!(plan.remove_rule(&rule("97", "75")), vec![]);
+
+        let changes = plan.add_rule(rule("97", "75"));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(
+            changes[0].after,
+            Some(Status::Repaired(pages(&[97, 75, 47, 61, 53])))
+        );
+
+        // unrelated to any update
+        assert_eq!(plan.add_rule(rule("11", "12")), vec![]);
+    }
+
+    #[test]
+    fn test_add_rule_creating_cycle() {
+        let mut plan = PrintPlan::parse(EXAMPLE_PLAN).unwrap();
+
+        let changes = plan.add_rule(rule("13", "29"));
+        let ids: Vec<usize> = changes.iter().map(|change| change.id).collect();
+        assert_eq!(ids, vec![1, 2, 4, 5]);
+        assert!(changes
+            .iter()
+            .all(|change| matches!(change.after, Some(Status::Unrepairable(_)))));
+    }
+
+    #[test]
+    fn test_add_and_remove_update() {
+        let mut plan = PrintPlan::parse(EXAMPLE_PLAN).unwrap();
+
+        let change = plan.add_update(pages(&[53, 47]));
+        assert_eq!(change.id, 6);
+        assert_eq!(change.before, None);
+        assert_eq!(change.after, Some(Status::Repaired(pages(&[47, 53]))));
+
+        let change = plan.remove_update(6).unwrap();
+        assert_eq!(change.after, None);
+        assert_eq!(status(&plan, 6), None);
+        assert_eq!(plan.remove_update(6), None);
+
+        // removed updates are not revalidated anymore
+        assert_eq!(plan.remove_rule(&rule("47", "53")).len(), 0);
+    }
+}
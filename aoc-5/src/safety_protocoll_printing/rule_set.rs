@@ -0,0 +1,161 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+// pages are identified by section names, which for the puzzle input are plain numbers
+pub type Page = String;
+pub type Rule = (Page, Page);
+
+// a rule which is broken by an update, together with the positions of both pages
+#[derive(Debug, PartialEq, Clone)]
+pub struct Violation {
+    pub before: Page,
+    pub after: Page,
+    pub before_index: usize,
+    pub after_index: usize,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(
+            f,
+            "page {} must come before {} but is at index {} vs {}",
+            self.before, self.after, self.before_index, self.after_index
+        );
+    }
+}
+
+// All ordering rules, indexed for constant time lookups of whether one page
+// has to be printed before another.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct RuleSet {
+    // all pages which have to be printed after the key page
+    successors: HashMap<Page, HashSet<Page>>,
+}
+
+impl RuleSet {
+    pub fn new(rules: &Vec<Rule>) -> RuleSet {
+        let mut rule_set = RuleSet::default();
+
+        for rule in rules {
+            rule_set.insert(rule.clone());
+        }
+
+        return rule_set;
+    }
+
+    pub fn insert(&mut self, (before, after): Rule) {
+        self.successors.entry(before).or_default().insert(after);
+    }
+
+    // returns whether the rule was part of the set
+    pub fn remove(&mut self, (before, after): &Rule) -> bool {
+        return self
+            .successors
+            .get_mut(before)
+            .is_some_and(|successors| successors.remove(after));
+    }
+
+    pub fn must_precede(&self, before: &Page, after: &Page) -> bool {
+        return self
+            .successors
+            .get(before)
+            .is_some_and(|successors| successors.contains(after));
+    }
+
+    // an update is ordered, if no page is required to come before any page preceding it
+    pub fn is_ordered(&self, update: &Vec<Page>) -> bool {
+        for (index, page) in update.iter().enumerate() {
+            for later_page in &update[index + 1..] {
+                if self.must_precede(later_page, page) {
+                    return false;
+                }
+            }
+        }
+        return true;
+    }
+
+    pub fn violations(&self, update: &Vec<Page>) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = vec![];
+
+        for (after_index, after) in update.iter().enumerate() {
+            for (offset, before) in update[after_index + 1..].iter().enumerate() {
+                if self.must_precede(before, after) {
+                    violations.push(Violation {
+                        before: before.clone(),
+                        after: after.clone(),
+                        before_index: after_index + 1 + offset,
+                        after_index,
+                    });
+                }
+            }
+        }
+        return violations;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RuleSet, Violation};
+    use crate::safety_protocoll_printing::tests::{pages, rules};
+
+    #[test]
+    fn test_is_ordered() {
+        let rule_set = RuleSet::new(&rules(&[(47, 53), (97, 13), (97, 61), (97, 47), (75, 29)]));
+
+        assert!(rule_set.must_precede(&"47".to_string(), &"53".to_string()));
+        assert!(!rule_set.must_precede(&"53".to_string(), &"47".to_string()));
+
+        assert!(rule_set.is_ordered(&pages(&[75, 47, 61, 53, 29])));
+        assert!(rule_set.is_ordered(&pages(&[13, 75])));
+        assert!(!rule_set.is_ordered(&pages(&[75, 29, 13, 97])));
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut rule_set = RuleSet::new(&rules(&[(47, 53)]));
+        assert!(rule_set.is_ordered(&pages(&[97, 47])));
+
+        rule_set.insert(("47".to_string(), "97".to_string()));
+        assert!(!rule_set.is_ordered(&pages(&[97, 47])));
+
+        assert!(rule_set.remove(&("47".to_string(), "97".to_string())));
+        assert!(!rule_set.remove(&("47".to_string(), "97".to_string())));
+        assert!(rule_set.is_ordered(&pages(&[97, 47])));
+    }
+
+    #[test]
+    fn test_violations() {
+        let rule_set = RuleSet::new(&rules(&[(47, 53), (97, 13), (97, 61), (97, 47), (75, 47)]));
+
+        let violations = rule_set.violations(&pages(&[61, 47, 53, 75, 97]));
+        assert_eq!(
+            violations,
+            vec![
+                Violation {
+                    before: "97".to_string(),
+                    after: "61".to_string(),
+                    before_index: 4,
+                    after_index: 0
+                },
+                Violation {
+                    before: "75".to_string(),
+                    after: "47".to_string(),
+                    before_index: 3,
+                    after_index: 1
+                },
+                Violation {
+                    before: "97".to_string(),
+                    after: "47".to_string(),
+                    before_index: 4,
+                    after_index: 1
+                }
+            ]
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "page 75 must come before 47 but is at index 3 vs 1"
+        );
+
+        assert_eq!(rule_set.violations(&pages(&[97, 61, 47, 53])), vec![]);
+    }
+}
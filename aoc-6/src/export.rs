@@ -0,0 +1,180 @@
+use std::io::Write;
+
+use crate::guard_avoidance_1::{find_guard, Guard, Map};
+use crate::guard_avoidance_2::trace_walk;
+use crate::guard_rules::GuardRules;
+
+// all colors used in the images, indexed by `color_index`
+const PALETTE: [u8; 24] = [
+    250, 250, 250, // free field
+    60, 60, 60, // obstacle
+    220, 40, 40, // placed obstacle
+    80, 140, 230, // way of the guard
+    240, 150, 30, // turn of the guard
+    40, 170, 60, // guard
+    170, 170, 170, // tried obstacle without a loop
+    0, 0, 0, // anything else
+];
+
+fn color_index(field: char) -> u8 {
+    return match field {
+        '.' => 0,
+        '#' => 1,
+        'O' => 2,
+        '|' | '-' | 'X' => 3,
+        '+' => 4,
+        '^' | '>' | 'v' | '<' => 5,
+        '█' => 6,
+        _ => 7,
+    };
+}
+
+// every field of the map becomes a square of `scale` x `scale` pixels
+fn pixels(map: &Map, scale: usize) -> (usize, usize, Vec<u8>) {
+    let width = map[0].len() * scale;
+    let height = map.len() * scale;
+    let mut pixels: Vec<u8> = Vec::with_capacity(width * height);
+
+    for line in map {
+        let row: Vec<u8> = line
+            .iter()
+            .flat_map(|field| vec![color_index(*field); scale])
+            .collect();
+
+        for _ in 0..scale {
+            pixels.extend(&row);
+        }
+    }
+
+    return (width, height, pixels);
+}
+
+// writes the map as it is, e.g. drawn by `trace_loop`, `draw_loop` or `draw_path`
+pub fn write_png<W: Write>(map: &Map, scale: usize, writer: W) -> Result<(), String> {
+    let (width, height, pixels) = pixels(map, scale);
+
+    let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(PALETTE.to_vec());
+
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(&pixels)
+        .map_err(|e| e.to_string())?;
+
+    return Ok(());
+}
+
+// Animates the guard walking over the map until it leaves it or runs into a loop. For big maps
+// `steps_per_frame` keeps the number of frames down.
+pub fn write_gif<W: Write>(
+    map: &Map,
+    rules: &GuardRules,
+    scale: usize,
+    steps_per_frame: usize,
+    writer: W,
+) -> Result<(), String> {
+    let initial_location = find_guard(map).ok_or("no guard found!".to_string())?;
+    let start = Guard::new(initial_location);
+    let (width, height, first_frame) = pixels(map, scale);
+
+    let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &PALETTE)
+        .map_err(|e| e.to_string())?;
+    encoder
+        .set_repeat(gif::Repeat::Infinite)
+        .map_err(|e| e.to_string())?;
+
+    let mut frames: Vec<Vec<u8>> = vec![first_frame];
+    let mut step: usize = 0;
+
+    let (_, last_map) = trace_walk(map.clone(), rules, start, &mut |map| {
+        step += 1;
+        if step.is_multiple_of(steps_per_frame) {
+            frames.push(pixels(map, scale).2);
+        }
+    });
+
+    // the last frame shows the whole way, no matter the number of steps
+    if !step.is_multiple_of(steps_per_frame) {
+        frames.push(pixels(&last_map, scale).2);
+    }
+
+    for frame in frames {
+        let mut frame = gif::Frame::from_indexed_pixels(width as u16, height as u16, frame, None);
+        frame.delay = 5; // in units of 10ms
+        encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+    }
+
+    return Ok(());
+}
+
+// ###############################################################################################################
+// ###############################################################################################################
+// # TESTS                                                                                                       #
+// ###############################################################################################################
+// ###############################################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::{pixels, write_gif, write_png, PALETTE};
+    use crate::guard_avoidance_1::parse_map;
+    use crate::guard_rules::GuardRules;
+
+    const EXAMPLE_MAP: &str = "....#.....
+             .........#
+             ..........
+             ..#.......
+             .......#..
+             ..........
+             .#..^.....
+             ........#.
+             #.........
+             ......#...";
+
+    #[test]
+    fn test_pixels() {
+        let map = parse_map("#.\nO+");
+        let (width, height, pixels) = pixels(&map, 2);
+
+        assert_eq!((width, height), (4, 4));
+        assert_eq!(pixels, vec![1, 1, 0, 0, 1, 1, 0, 0, 2, 2, 4, 4, 2, 2, 4, 4]);
+    }
+
+    #[test]
+    fn test_write_png() {
+        let map = parse_map(EXAMPLE_MAP);
+        let mut image: Vec<u8> = vec![];
+        write_png(&map, 3, &mut image).unwrap();
+
+        let decoder = png::Decoder::new(image.as_slice());
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+
+        assert_eq!((info.width, info.height), (30, 30));
+        assert_eq!(info.palette.as_deref(), Some(&PALETTE[..]));
+    }
+
+    #[test]
+    fn test_write_gif() {
+        let map = parse_map(EXAMPLE_MAP);
+        let mut animation: Vec<u8> = vec![];
+        // the guard needs 55 steps (including turns) to leave the map
+        write_gif(&map, &GuardRules::default(), 1, 10, &mut animation).unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(animation.as_slice()).unwrap();
+
+        let mut frames: Vec<Vec<u8>> = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push(frame.buffer.to_vec());
+        }
+
+        // the initial map, every 10th step and the final way
+        assert_eq!(frames.len(), 7);
+        // the guard left the map at the bottom, its way leads there
+        assert_eq!(frames[6][9 * 10 + 7], 3);
+        assert_eq!(frames[0][6 * 10 + 4], 5);
+    }
+}
@@ -1,4 +1,8 @@
-#[derive(Debug, PartialEq, Copy, Clone)]
+use std::collections::HashMap;
+
+use crate::guard_rules::GuardRules;
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum Perspective {
     Up,
     Down,
@@ -16,7 +20,8 @@ pub fn perspective_char(perspective: &Perspective) -> char {
 }
 
 pub type Location = (usize, usize, Perspective);
-pub type Map<'a> = Vec<Vec<char>>;
+pub type Position = (usize, usize);
+pub type Map = Vec<Vec<char>>;
 
 pub fn parse_map(map: &str) -> Map {
     let map = map.trim();
@@ -38,7 +43,7 @@ fn perspective(c: &char) -> Result<Perspective, String> {
     };
 }
 
-fn rotate(perspective: &Perspective) -> Perspective {
+pub fn rotate(perspective: &Perspective) -> Perspective {
     return match perspective {
         Perspective::Up => Perspective::Right,
         Perspective::Right => Perspective::Down,
@@ -47,6 +52,16 @@ fn rotate(perspective: &Perspective) -> Perspective {
     };
 }
 
+// index of the perspective, e.g. to keep visited states in a flat list
+pub fn perspective_index(perspective: &Perspective) -> usize {
+    return match perspective {
+        Perspective::Up => 0,
+        Perspective::Right => 1,
+        Perspective::Down => 2,
+        Perspective::Left => 3,
+    };
+}
+
 pub fn find_guard(map: &Map) -> Option<Location> {
     let guard_shapes = ['^', '<', '>', 'v'];
 
@@ -65,102 +80,113 @@ pub fn find_guard(map: &Map) -> Option<Location> {
     return None;
 }
 
-fn go_up(map: &Map, location: &Location) -> (Option<Location>, Option<Location>) {
-    let (x, y, perspective) = location.clone();
-
-    if y > 0 {
-        let new_y = y - 1;
-        let next_field = map[new_y][x];
+pub fn find_guards(map: &Map) -> Vec<Location> {
+    let perspectives = [
+        Perspective::Up,
+        Perspective::Down,
+        Perspective::Left,
+        Perspective::Right,
+    ];
+    let mut guards: Vec<Location> = vec![];
 
-        if next_field == '#' || next_field == 'O' {
-            // let original_target = next_field;
-            let new_perspective = rotate(&perspective);
-            return (Some((x, y, new_perspective)), Some((x, new_y, perspective)));
-        } else {
-            return (Some((x, new_y, perspective)), None);
+    for (y, line) in map.iter().enumerate() {
+        for (x, field) in line.iter().enumerate() {
+            if let Some(perspective) = perspectives
+                .iter()
+                .find(|perspective| perspective_char(perspective) == *field)
+            {
+                guards.push((x, y, *perspective));
+            }
         }
     }
 
-    return (None, None);
+    return guards;
 }
 
-fn go_down(map: &Map, location: &Location) -> (Option<Location>, Option<Location>) {
-    let (x, y, perspective) = location.clone();
-
-    if y < map.len() - 1 {
-        let new_y = y + 1;
-        let next_field = map[new_y][x];
+// The location alone is not enough to know what a guard does next, with alternating turns
+// it also depends on the number of turns so far.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Guard {
+    pub location: Location,
+    pub turns: usize,
+}
 
-        if next_field == '#' || next_field == 'O' {
-            let new_perspective = rotate(&perspective);
-            return (Some((x, y, new_perspective)), Some((x, new_y, perspective)));
-        } else {
-            return (Some((x, new_y, perspective)), None);
-        }
+impl Guard {
+    pub fn new(location: Location) -> Guard {
+        return Guard { location, turns: 0 };
     }
-
-    return (None, None);
 }
 
-fn go_right(map: &Map, location: &Location) -> (Option<Location>, Option<Location>) {
-    let (x, y, perspective) = location.clone();
-    let first_line = map.first().unwrap();
-
-    if x < first_line.len() - 1 {
-        let new_x = x + 1;
-
-        let next_field = map[y][new_x];
-
-        if next_field == '#' || next_field == 'O' {
-            let new_perspective = rotate(&perspective);
-            return (Some((x, y, new_perspective)), Some((new_x, y, perspective)));
-        } else {
-            return (Some((new_x, y, perspective)), None);
+// The simulation core shared by all walks and loop finders: it only looks at the map and the
+// guard following the given rules and never changes the map itself. Besides the next guard it
+// returns the blocking field the guard would have walked onto if it turned.
+pub fn predict_next_step(
+    map: &Map,
+    rules: &GuardRules,
+    guard: &Guard,
+) -> (Option<Guard>, Option<Location>) {
+    let (x, y, perspective) = guard.location;
+
+    if let Some((next_x, next_y)) = rules.ahead(map, guard.location) {
+        if rules.is_blocking(map[next_y][next_x]) {
+            let turned = Guard {
+                location: (x, y, rules.turn(perspective, guard.turns)),
+                turns: guard.turns + 1,
+            };
+            return (Some(turned), Some((next_x, next_y, perspective)));
         }
-    } else {
-        return (None, None); // left the Map
+
+        let moved = Guard {
+            location: (next_x, next_y, perspective),
+            turns: guard.turns,
+        };
+        return (Some(moved), None);
     }
+
+    return (None, None); // left the map
 }
 
-fn go_left(map: &Map, location: &Location) -> (Option<Location>, Option<Location>) {
-    let (x, y, perspective) = location.clone();
+// Walks the guard until it leaves the map or ends up in a loop. Returns all its locations
+// and, in case of a loop, the index of the location the loop starts at.
+pub fn walk(map: &Map, rules: &GuardRules, start: Guard) -> (Vec<Location>, Option<usize>) {
+    let mut walked: Vec<Location> = vec![];
+    let mut walked_indexes: HashMap<(Location, usize), usize> = HashMap::new();
+    let mut guard = start;
 
-    if x > 0 {
-        let new_x = x - 1;
+    loop {
+        let state = (guard.location, rules.phase(guard.turns));
 
-        let next_field = map[y][new_x];
+        if let Some(index) = walked_indexes.get(&state) {
+            return (walked, Some(*index));
+        }
+        walked_indexes.insert(state, walked.len());
+        walked.push(guard.location);
 
-        if next_field == '#' || next_field == 'O' {
-            let new_perspective = rotate(&perspective);
-            return (Some((x, y, new_perspective)), Some((new_x, y, perspective)));
-        } else {
-            return (Some((new_x, y, perspective)), None);
+        match predict_next_step(map, rules, &guard) {
+            (Some(next_guard), _) => guard = next_guard,
+            (None, _) => return (walked, None),
         }
     }
-
-    return (None, None);
 }
 
-pub fn predict_next_step(map: &Map) -> (Location, Option<Location>, Option<Location>) {
-    if let Some(location) = find_guard(&map) {
-        let (_, _, perspective) = location.clone();
+// all positions visited by any of the guards, in the order they got visited guard by guard
+pub fn predict_path_positions(map: &Map, rules: &GuardRules) -> (usize, Vec<Position>) {
+    let map_width = map[0].len();
+    let mut visited: Vec<bool> = vec![false; map_width * map.len()];
+    let mut steps: Vec<Position> = vec![];
 
-        let (next_location, original_target) = match &perspective {
-            Perspective::Up => go_up(&map, &location),
-            Perspective::Right => go_right(&map, &location),
-            Perspective::Down => go_down(&map, &location),
-            Perspective::Left => go_left(&map, &location),
-        };
+    for guard in find_guards(&map) {
+        let (walked, _) = walk(&map, &rules, Guard::new(guard));
 
-        return (location, next_location, original_target);
+        for (x, y, _) in walked {
+            if !visited[y * map_width + x] {
+                visited[y * map_width + x] = true;
+                steps.push((x, y));
+            }
+        }
     }
 
-    println!("last map state:\n\n{}", show_map(&map));
-    panic!("no guard found!");
-}
-
-fn mark_last_position(map: &mut Map, x: usize, y: usize) {
-    map[y][x] = 'X';
+    return (steps.len(), steps);
 }
 
 pub fn update_position(map: &mut Map, x: usize, y: usize, new_perspective: Perspective) {
@@ -181,27 +207,14 @@ pub fn show_map(map: &Map) -> String {
     return map.to_string();
 }
 
-pub fn predict_path_positions<'b>(map: &Map) -> (Map<'b>, String, usize) {
+// marks every visited position of the guard with an `X`
+pub fn draw_path(map: &Map, steps: &Vec<Position>) -> Map {
     let mut map = map.clone();
 
-    loop {
-        let (before_location, after_location, _) = predict_next_step(&map);
-
-        // update_map(&mut map, &before_location, &after_location);
-        let (x, y, _) = before_location;
-        mark_last_position(&mut map, x, y);
-
-        if let Some(after_location) = after_location {
-            let (x, y, new_perspective) = after_location;
-            update_position(&mut map, x, y, new_perspective);
-        } else {
-            break;
-        }
+    for (x, y) in steps {
+        map[*y][*x] = 'X';
     }
-
-    let rendered_map = show_map(&map);
-    let steps = &rendered_map.chars().filter(|c| *c == 'X').count();
-    return (map, rendered_map, *steps);
+    return map;
 }
 
 // ###############################################################################################################
@@ -213,8 +226,17 @@ pub fn predict_path_positions<'b>(map: &Map) -> (Map<'b>, String, usize) {
 #[cfg(test)]
 mod tests {
     use super::{
-        find_guard, parse_map, perspective, predict_next_step, predict_path_positions, Perspective,
+        draw_path, find_guard, parse_map, perspective, predict_next_step, predict_path_positions,
+        show_map, Guard, Location, Map, Perspective,
     };
+    use crate::guard_rules::GuardRules;
+
+    // the location of the single guard on the map after its next step
+    fn next_location(map: &Map) -> Option<Location> {
+        let guard = Guard::new(find_guard(&map).unwrap());
+        let (next_guard, _) = predict_next_step(&map, &GuardRules::default(), &guard);
+        return next_guard.map(|guard| guard.location);
+    }
 
     #[test]
     fn test_going_up() {
@@ -222,14 +244,14 @@ mod tests {
             ".....#....
              .#..^.....",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((4, 0, Perspective::Up)));
     }
 
     #[test]
     fn test_going_up_out_of_map() {
         let map = parse_map("...^#....."); // leaving map
-        let (_, after_locatoon, _) = predict_next_step(&map);
+        let after_locatoon = next_location(&map);
         assert_eq!(after_locatoon, None);
     }
 
@@ -239,7 +261,7 @@ mod tests {
             "....#.....
              .#..^.....",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((4, 1, Perspective::Right)));
     }
 
@@ -249,7 +271,7 @@ mod tests {
             "....#.....
              .#..>.#...",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((5, 1, Perspective::Right)));
     }
 
@@ -259,14 +281,14 @@ mod tests {
             "....#.....
              .#..>#....",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((4, 1, Perspective::Down)));
     }
 
     #[test]
     fn test_going_right_out_of_map() {
         let map = parse_map(".#...#...>"); // leaving map
-        let (_, after_location, _) = predict_next_step(&map);
+        let after_location = next_location(&map);
         assert_eq!(after_location, None);
     }
 
@@ -276,7 +298,7 @@ mod tests {
             "....#.v...
              .#...#....",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((6, 1, Perspective::Down)));
     }
 
@@ -286,14 +308,14 @@ mod tests {
             "....#.v...
              .#....#...",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((6, 0, Perspective::Left)));
     }
 
     #[test]
     fn test_going_down_leaving_the_map() {
         let map = parse_map("....#.v...");
-        let (_, after_location, _) = predict_next_step(&map);
+        let after_location = next_location(&map);
         assert_eq!(after_location, None);
     }
 
@@ -303,7 +325,7 @@ mod tests {
             "....#.....
              .#.<.#....",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((2, 1, Perspective::Left)));
     }
 
@@ -313,7 +335,7 @@ mod tests {
             "....#.....
              .#<..#....",
         );
-        let (_, next_location, _) = predict_next_step(&map);
+        let next_location = next_location(&map);
         assert_eq!(next_location, Some((2, 1, Perspective::Up)));
     }
 
@@ -323,7 +345,7 @@ mod tests {
             "....#.....
              <....#....",
         );
-        let (_, after_location, _) = predict_next_step(&map);
+        let after_location = next_location(&map);
         assert_eq!(after_location, None);
     }
 
@@ -365,6 +387,62 @@ mod tests {
         assert_eq!(p, Ok(Perspective::Right));
     }
 
+    #[test]
+    fn test_predict_direct_path_out() {
+        let map = parse_map(
+            "..........
+             .........#
+             ..........
+             ..#.......
+             .......#..
+             ..........
+             .#..^.....
+             ........#.
+             #.........
+             ......#...",
+        );
+        let (steps, _) = predict_path_positions(&map, &GuardRules::default());
+
+        assert_eq!(steps, 7);
+    }
+
+    #[test]
+    fn test_predict_one_uturn() {
+        let map = parse_map(
+            "....#.....
+             ......#...
+             .#..^.....",
+        );
+        let (steps, _) = predict_path_positions(&map, &GuardRules::default());
+
+        assert_eq!(steps, 4);
+    }
+
+    #[test]
+    fn test_predict_two_turns() {
+        let map = parse_map(
+            "....#.....
+             ........#.
+             .#..^.....",
+        );
+        let (steps, _) = predict_path_positions(&map, &GuardRules::default());
+
+        assert_eq!(steps, 6);
+    }
+
+    #[test]
+    fn test_predict_multiple_turns() {
+        let map = parse_map(
+            "....#.....
+             ........#.
+             .#..^.....
+             .#.....#..",
+        );
+        let (steps, _) = predict_path_positions(&map, &GuardRules::default());
+
+        assert_eq!(steps, 12);
+    }
+
     #[test]
     fn test_predict_path() {
         let map = parse_map(
@@ -379,11 +457,10 @@ mod tests {
              #.........
              ......#...",
         );
-
-        let (_, map_after, steps) = predict_path_positions(&map);
+        let (steps, path) = predict_path_positions(&map, &GuardRules::default());
 
         assert_eq!(
-            map_after,
+            show_map(&draw_path(&map, &path)),
             "....#.....
 ....XXXXX#
 ....X...X.
@@ -1,7 +1,53 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+
 use crate::guard_avoidance_1::{
-    find_guard, perspective_char, predict_next_step, show_map, update_position, Location, Map,
-    Perspective,
+    find_guards, perspective_index, predict_next_step, update_position, walk, Guard, Location, Map,
+    Perspective, Position,
 };
+use crate::guard_rules::GuardRules;
+
+mod jump_table;
+use jump_table::JumpTable;
+
+// Both strategies share the simulation core of `predict_next_step` and find the same loops.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LoopFinder {
+    // draws the way of the guard into a copy of the map for every tried obstacle
+    Tracing,
+    // jumps from obstacle to obstacle, only keeping track of the turns of the guard
+    Fast,
+}
+
+// An obstacle which traps a guard in a loop, together with where that guard starts to walk
+// into it. The loop itself is only walked on demand.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Loop {
+    pub obstacle: Position,
+    pub start: Guard,
+}
+
+impl Loop {
+    // Walks the guard step by step with the obstacle placed. The cycle holds all locations
+    // of the loop in the order the guard walks them, starting with the first repeated one.
+    pub fn cycle(&self, map: &Map, rules: &GuardRules) -> Result<Vec<Location>, String> {
+        let mut map = map.clone();
+        let (obstacle_x, obstacle_y) = self.obstacle;
+        map[obstacle_y][obstacle_x] = 'O';
+
+        let (mut walked, loop_start) = walk(&map, &rules, self.start);
+
+        return match loop_start {
+            Some(loop_start) => Ok(walked.split_off(loop_start)),
+            None => Err(format!(
+                "the guard leaves the map despite the obstacle at line: {:#03} / col: {:#03}",
+                obstacle_y, obstacle_x
+            )),
+        };
+    }
+}
 
 fn way_marker(perspective: Perspective) -> char {
     return match perspective {
@@ -12,129 +58,301 @@ fn way_marker(perspective: Perspective) -> char {
     };
 }
 
-fn check_if_we_hit_the_obstacle_again(obstacles: &mut Vec<String>, obstacle: Location) -> bool {
-    let (x, y, perspective) = obstacle;
-    let original_perspective = perspective_char(&perspective);
+// The guard is in a loop as soon as it runs into the same obstacle from the same side again,
+// in the same phase of its turns.
+fn check_if_we_hit_the_obstacle_again(
+    obstacles: &mut HashSet<(Location, usize)>,
+    obstacle: (Location, usize),
+) -> bool {
+    // `insert` is false if we've seen that obstacle before, yeah, we've found a loop
+    return !obstacles.insert(obstacle);
+}
+
+// Visited turns of the guard for all tried obstacles, in both phases of alternating turns.
+// Instead of clearing it for every try, each try gets its own mark.
+struct VisitedStates {
+    width: usize,
+    marks: Vec<u32>,
+    current_mark: u32,
+}
+
+impl VisitedStates {
+    fn new(map: &Map) -> VisitedStates {
+        return VisitedStates {
+            width: map[0].len(),
+            marks: vec![0; map[0].len() * map.len() * 8],
+            current_mark: 0,
+        };
+    }
+
+    fn start_new_walk(&mut self) {
+        self.current_mark += 1;
+    }
 
-    let key = format!("{}:{}:{}", x, y, original_perspective).to_string();
+    // returns false if the state has been visited before in the current walk
+    fn visit(&mut self, (x, y, perspective): Location, phase: usize) -> bool {
+        let index = ((y * self.width + x) * 4 + perspective_index(&perspective)) * 2 + phase;
 
-    if obstacles.contains(&key) {
-        // yeah, we've found a loop
+        if self.marks[index] == self.current_mark {
+            return false;
+        }
+        self.marks[index] = self.current_mark;
         return true;
     }
+}
 
-    if !obstacles.contains(&key) {
-        // memorize obstacle, so we can recognize later if we hit it again
-        obstacles.push(key);
-        return false;
+// Every position of the original path is a candidate for a new obstacle (except the start),
+// paired with the guard right before it would walk onto it for the first time. Up to there
+// the path does not change by the new obstacle, so walks can start from there. The guard has
+// to leave the map on its original path.
+fn obstacle_candidates(
+    map: &Map,
+    rules: &GuardRules,
+    initial_location: Location,
+) -> Vec<(Position, Guard)> {
+    let map_width = map[0].len();
+    let (x, y, _) = initial_location;
+
+    let mut visited: Vec<bool> = vec![false; map_width * map.len()];
+    visited[y * map_width + x] = true;
+
+    let mut candidates: Vec<(Position, Guard)> = vec![];
+    let mut guard = Guard::new(initial_location);
+
+    while let (Some(next_guard), _) = predict_next_step(&map, &rules, &guard) {
+        let (new_x, new_y, _) = next_guard.location;
+
+        if !visited[new_y * map_width + new_x] {
+            visited[new_y * map_width + new_x] = true;
+            candidates.push(((new_x, new_y), guard));
+        }
+        guard = next_guard;
     }
-    return true;
+
+    return candidates;
 }
 
-pub fn walk_and_check_for_loop(
+// walks the guard with an additional obstacle and returns whether it ends up in a loop,
+// together with the map showing its way (`|`, `-` and `+` for turns)
+pub fn trace_loop(
     map: &Map,
-    original_guards_path: &Map,
+    rules: &GuardRules,
     obstacle_x: usize,
     obstacle_y: usize,
-) -> bool {
+    start: Guard,
+) -> (bool, Map) {
     let mut map = map.clone();
-    let mut obstacles: Vec<String> = vec![];
-    let mut crossing: Option<(usize, usize)> = None;
+    map[obstacle_y][obstacle_x] = 'O'; // place obstacle
 
-    if original_guards_path[obstacle_y][obstacle_x] == 'X' {
-        map[obstacle_y][obstacle_x] = 'O'; // place obstacle
-    } else {
-        // obstacles that are not in the original path of the guard do not change a thing, so let's skip them
-        return false;
-    }
+    return trace_walk(map, rules, start, &mut |_| {});
+}
+
+// Draws the way of the guard into the map until it either leaves the map or ends up in a loop,
+// `on_step` sees the map after every step of the guard.
+pub fn trace_walk(
+    mut map: Map,
+    rules: &GuardRules,
+    start: Guard,
+    on_step: &mut dyn FnMut(&Map),
+) -> (bool, Map) {
+    let mut obstacles: HashSet<(Location, usize)> = HashSet::new();
+    let mut turned = false;
+    let mut guard = start;
 
     loop {
-        let (before_location, after_location, original_target) = predict_next_step(&map);
-        // let's get current location
-        let (x, y, old_perspective) = before_location;
-        //let's get the location, the guard approaches next
+        let (x, y, old_perspective) = guard.location;
+        let (next_guard, original_target) = predict_next_step(&map, &rules, &guard);
 
-        if let Some((new_x, new_y, new_perspective)) = after_location {
-            if let Some((cx, cy)) = crossing {
-                map[cy][cx] = '+';
-                crossing = None;
-            };
+        // the guard leaves its current position, mark it as way or crossing if it turned there
+        let way = if turned {
+            '+'
+        } else {
+            way_marker(old_perspective)
+        };
 
+        if let Some(next_guard) = next_guard {
             // in case of turns, we would have hit a different, original target. Let's memorize those,
             // to see if we hit one again. If so, we've found a loop!
             if let Some(original_target) = original_target {
-                if check_if_we_hit_the_obstacle_again(&mut obstacles, original_target) {
-                    return true;
+                let hit = (original_target, rules.phase(guard.turns));
+                if check_if_we_hit_the_obstacle_again(&mut obstacles, hit) {
+                    return (true, map);
                 }
-                // remember if we saw a crossing, so we can draw it in the next round
-                // without removing the guard itself. Because in this round the guard is only turning around.
-                crossing = Some((x, y));
+                // in this round the guard is only turning around, the crossing gets drawn
+                // as soon as it moves on
+                turned = true;
+            } else {
+                map[y][x] = way;
+                turned = false;
             }
 
             // draw new guard position to the map
+            let (new_x, new_y, new_perspective) = next_guard.location;
             update_position(&mut map, new_x, new_y, new_perspective);
+            on_step(&map);
 
-            // when we are not redirected
-            if let None = original_target {
-                // draw way of the guard to the map
-                map[y][x] = way_marker(old_perspective);
-            }
+            guard = next_guard;
         } else {
             // there is no guard anymore but we want to mark its last path
-            map[y][x] = way_marker(old_perspective);
-            break;
+            map[y][x] = way;
+            on_step(&map);
+            return (false, map);
         }
     }
+}
 
+// Walks the guard from obstacle to obstacle with one additional obstacle placed. Looking at
+// the turns is enough, as a loop always repeats those as well.
+fn walk_and_check_for_loop(
+    jump_table: &JumpTable,
+    rules: &GuardRules,
+    visited: &mut VisitedStates,
+    obstacle: Position,
+    start: Guard,
+) -> bool {
+    visited.start_new_walk();
+
+    let (mut x, mut y, mut perspective) = start.location;
+    let mut turns = start.turns;
+
+    while let Some((stop_x, stop_y)) =
+        jump_table.next_stop_with_obstacle((x, y), perspective, obstacle)
+    {
+        perspective = rules.turn(perspective, turns);
+        turns += 1;
+        (x, y) = (stop_x, stop_y);
+
+        if !visited.visit((x, y, perspective), rules.phase(turns)) {
+            return true;
+        }
+    }
+
+    // left the map
     return false;
 }
 
-pub fn find_loops(map: &Map, original_guards_path: &Map) -> u32 {
-    let map_width = original_guards_path[0].len(); // horizontal
-    let map_length = original_guards_path.len(); // vertical
+// draws the placed obstacle and the way of the guard within the loop (`|`, `-` and `+` for turns)
+pub fn draw_loop(map: &Map, found_loop: &Loop, cycle: &[Location]) -> Map {
+    let mut map = map.clone();
+    let (obstacle_x, obstacle_y) = found_loop.obstacle;
+    map[obstacle_y][obstacle_x] = 'O';
 
-    let (guard_start_x, guard_start_y, _) = find_guard(&map).unwrap();
+    draw_way(&mut map, cycle);
+    return map;
+}
 
-    let mut loop_count: u32 = 0;
-    let mut coords: Vec<(usize, usize)> = vec![];
-    let mut checked: Vec<(usize, usize)> = vec![];
+// marks the walked locations with `|` and `-`, or `+` where the guard crossed or turned
+pub fn draw_way(map: &mut Map, walked: &[Location]) {
+    for (x, y, perspective) in walked {
+        let way = way_marker(*perspective);
 
-    for obstacle_y in 0..map_length {
-        for obstacle_x in 0..map_width {
-            println!(
-                "line: {} / col: {}",
-                format!("{:#03}", obstacle_y),
-                format!("{:#03}", obstacle_x)
-            );
+        map[*y][*x] = match map[*y][*x] {
+            '|' | '-' if map[*y][*x] != way => '+',
+            '+' => '+',
+            _ => way,
+        };
+    }
+}
 
-            if obstacle_x == guard_start_x && obstacle_y == guard_start_y {
-                continue; // don't try the guards start location for a new obstacle
-            }
+// marks all tried obstacles with `█` and the ones trapping the guard in a loop with `O`
+pub fn draw_obstacles(map: &Map, tried: &Vec<Position>, loops: &Vec<Loop>) -> Map {
+    let mut map = map.clone();
 
-            if walk_and_check_for_loop(&map, &original_guards_path, obstacle_x, obstacle_y) {
-                coords.push((obstacle_x, obstacle_y));
-                loop_count += 1;
-            } else {
-                checked.push((obstacle_x, obstacle_y));
-            }
+    for (x, y) in tried {
+        if map[*y][*x] == '.' {
+            map[*y][*x] = '█';
         }
     }
 
-    let mut map = original_guards_path.clone();
+    for found_loop in loops {
+        let (x, y) = found_loop.obstacle;
+        map[y][x] = 'O';
+    }
+    return map;
+}
+
+// gets called with the number of already checked obstacles and the number of all obstacles to check
+pub type Progress<'a> = &'a (dyn Fn(usize, usize) + Sync);
+
+// Only guards leaving the map on their own can be trapped by a new obstacle, for all others it
+// makes no difference. So with wrap-around there is nothing to find. An obstacle counts as soon
+// as it traps one of the guards, guards don't block each other. All candidates are checked in
+// parallel, the found loops are the same as checking them one by one.
+pub fn find_loops(
+    map: &Map,
+    rules: &GuardRules,
+    finder: LoopFinder,
+    progress: Option<Progress>,
+) -> Vec<Loop> {
+    let guards = find_guards(&map);
+
+    // every obstacle with the guards to start from, in the order the guards pass them
+    let mut candidates: Vec<(Position, Vec<Guard>)> = vec![];
+    let mut candidate_indexes: HashMap<Position, usize> = HashMap::new();
 
-    for (x, y) in checked {
-        if original_guards_path[y][x] == 'X' {
-            map[y][x] = '█';
+    for guard in &guards {
+        if walk(&map, &rules, Guard::new(*guard)).1.is_some() {
+            continue; // in a loop already
         }
-    }
 
-    for (x, y) in coords {
-        map[y][x] = 'O';
+        for (obstacle, start) in obstacle_candidates(&map, &rules, *guard) {
+            if guards.iter().any(|(x, y, _)| (*x, *y) == obstacle) {
+                continue; // there is a guard standing
+            }
+
+            match candidate_indexes.get(&obstacle) {
+                Some(index) => candidates[*index].1.push(start),
+                None => {
+                    candidate_indexes.insert(obstacle, candidates.len());
+                    candidates.push((obstacle, vec![start]));
+                }
+            }
+        }
     }
 
-    println!("\n\n{}\n\n", show_map(&map));
+    let jump_table = JumpTable::new(&map, &rules);
+    let checked_count = AtomicUsize::new(0);
+
+    // every thread of the pool reuses its own visited states
+    let loops: Vec<Loop> = candidates
+        .par_iter()
+        .map_init(
+            || VisitedStates::new(&map),
+            |visited, (obstacle, starts)| {
+                let (obstacle_x, obstacle_y) = *obstacle;
+                let trapped = starts.iter().find(|start| {
+                    return match finder {
+                        LoopFinder::Tracing => {
+                            trace_loop(&map, &rules, obstacle_x, obstacle_y, **start).0
+                        }
+                        LoopFinder::Fast => walk_and_check_for_loop(
+                            &jump_table,
+                            &rules,
+                            visited,
+                            *obstacle,
+                            **start,
+                        ),
+                    };
+                });
 
-    return loop_count;
+                if let Some(progress) = progress {
+                    progress(
+                        checked_count.fetch_add(1, Ordering::Relaxed) + 1,
+                        candidates.len(),
+                    );
+                }
+
+                let start = trapped?; // none of the guards ends up in a loop
+                return Some(Loop {
+                    obstacle: *obstacle,
+                    start: *start,
+                });
+            },
+        )
+        .flatten()
+        .collect();
+
+    return loops;
 }
 
 // ###############################################################################################################
@@ -145,12 +363,18 @@ pub fn find_loops(map: &Map, original_guards_path: &Map) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use crate::{guard_avoidance_1::parse_map, guard_avoidance_2::find_loops};
+    use std::sync::Mutex;
 
-    #[test]
-    fn test_predict_path() {
-        let map = parse_map(
-            "....#.....
+    use crate::{
+        guard_avoidance_1::{find_guard, parse_map, show_map, Guard, Position},
+        guard_avoidance_2::{
+            draw_loop, find_loops, obstacle_candidates, trace_loop, walk_and_check_for_loop,
+            JumpTable, Loop, LoopFinder, VisitedStates,
+        },
+        guard_rules::GuardRules,
+    };
+
+    const EXAMPLE_MAP: &str = "....#.....
              .........#
              ..........
              ..#.......
@@ -159,24 +383,162 @@ mod tests {
              .#..^.....
              ........#.
              #.........
-             ......#...",
+             ......#...";
+
+    #[test]
+    fn test_predict_path() {
+        let map = parse_map(EXAMPLE_MAP);
+        let loops = find_loops(&map, &GuardRules::default(), LoopFinder::Fast, None);
+        let obstacles: Vec<Position> = loops.iter().map(|found_loop| found_loop.obstacle).collect();
+
+        // in the order the guard passes them on its original path
+        assert_eq!(
+            obstacles,
+            vec![(3, 6), (6, 7), (3, 8), (1, 8), (7, 7), (7, 9)]
         );
+    }
+
+    #[test]
+    fn test_finders_agree() {
+        let map = parse_map(EXAMPLE_MAP);
+
+        let tracing = find_loops(&map, &GuardRules::default(), LoopFinder::Tracing, None);
+        let fast = find_loops(&map, &GuardRules::default(), LoopFinder::Fast, None);
+        assert_eq!(tracing, fast);
+
+        let rules = GuardRules::default();
+        let initial_location = find_guard(&map).unwrap();
+        let jump_table = JumpTable::new(&map, &rules);
+        let mut visited = VisitedStates::new(&map);
+
+        for ((x, y), start) in obstacle_candidates(&map, &rules, initial_location) {
+            // starting from the very beginning or right in front of the obstacle doesn't matter
+            let (is_loop, _) = trace_loop(&map, &rules, x, y, Guard::new(initial_location));
+            assert_eq!(is_loop, trace_loop(&map, &rules, x, y, start).0);
+            assert_eq!(
+                is_loop,
+                walk_and_check_for_loop(&jump_table, &rules, &mut visited, (x, y), start)
+            );
+        }
+    }
 
-        let original_guards_path = parse_map(
+    #[test]
+    fn test_progress() {
+        let map = parse_map(EXAMPLE_MAP);
+        let reported: Mutex<Vec<(usize, usize)>> = Mutex::new(vec![]);
+        let progress = |checked: usize, total: usize| {
+            reported.lock().unwrap().push((checked, total));
+        };
+
+        let rules = GuardRules::default();
+        assert_eq!(
+            find_loops(&map, &rules, LoopFinder::Fast, Some(&progress)).len(),
+            6
+        );
+
+        let mut reported = reported.into_inner().unwrap();
+        reported.sort();
+        let expected: Vec<(usize, usize)> = (1..=40).map(|checked| (checked, 40)).collect();
+        assert_eq!(reported, expected);
+    }
+
+    #[test]
+    fn test_guard_in_a_loop_already() {
+        let map = parse_map(
+            ".#...
+             .^..#
+             .....
+             #....
+             ...#.",
+        );
+
+        // no obstacle makes a difference for a guard which never leaves the map
+        assert_eq!(
+            find_loops(&map, &GuardRules::default(), LoopFinder::Fast, None),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_loop_cycle() {
+        let map = parse_map(EXAMPLE_MAP);
+        let rules = GuardRules::default();
+        let initial_location = find_guard(&map).unwrap();
+        let start = Guard::new(initial_location);
+
+        let found_loop = Loop {
+            obstacle: (3, 6),
+            start,
+        };
+        let cycle = found_loop.cycle(&map, &rules).unwrap();
+        // the guard starts right within the loop
+        assert_eq!(cycle[0], initial_location);
+        assert_eq!(cycle.len(), 22);
+
+        let no_loop = Loop {
+            obstacle: (0, 0),
+            start,
+        };
+        assert!(no_loop.cycle(&map, &rules).is_err());
+    }
+
+    #[test]
+    fn test_draw_loop() {
+        let map = parse_map(EXAMPLE_MAP);
+        let rules = GuardRules::default();
+        let loops = find_loops(&map, &rules, LoopFinder::Fast, None);
+        let cycle = loops[0].cycle(&map, &rules).unwrap();
+
+        assert_eq!(
+            show_map(&draw_loop(&map, &loops[0], &cycle)),
             "....#.....
-             ....XXXXX#
-             ....X...X.
-             ..#.X...X.
-             ..XXXXX#X.
-             ..X.X.X.X.
-             .#XXXXXXX.
-             .XXXXXXX#.
-             #XXXXXXX..
-             ......#X..",
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O+---+.
+........#.
+#.........
+......#..."
         );
+    }
+
+    #[test]
+    fn test_obstacle_candidates() {
+        let map = parse_map(EXAMPLE_MAP);
+        let initial_location = find_guard(&map).unwrap();
+        let candidates = obstacle_candidates(&map, &GuardRules::default(), initial_location);
+
+        // all visited positions but the start of the guard
+        assert_eq!(candidates.len(), 40);
+        assert_eq!(candidates[0], ((4, 5), Guard::new(initial_location)));
+    }
 
-        let loops = find_loops(&map, &original_guards_path);
+    #[test]
+    fn test_trace_loop() {
+        let map = parse_map(EXAMPLE_MAP);
+        let rules = GuardRules::default();
+        let start = Guard::new(find_guard(&map).unwrap());
+
+        let (is_loop, traced_map) = trace_loop(&map, &rules, 3, 6, start);
+        assert!(is_loop);
+        assert_eq!(
+            show_map(&traced_map),
+            "....#.....
+....^---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O+---+.
+........#.
+#.........
+......#..."
+        );
 
-        assert_eq!(loops, 6);
+        let (is_loop, traced_map) = trace_loop(&map, &rules, 0, 0, start);
+        assert!(!is_loop);
+        assert_eq!(traced_map[9][7], '|');
     }
 }
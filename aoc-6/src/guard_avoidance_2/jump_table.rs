@@ -0,0 +1,156 @@
+use crate::guard_avoidance_1::{perspective_index, Map, Perspective, Position};
+use crate::guard_rules::GuardRules;
+
+// For every position and perspective the field the guard walks up to before it has to
+// turn at the next blocking field, or `None` if it leaves the map on the way. Guards wrapping
+// around never leave the map, so the loop finders never jump with them.
+pub struct JumpTable {
+    width: usize,
+    stops: Vec<Option<Position>>,
+}
+
+impl JumpTable {
+    pub fn new(map: &Map, rules: &GuardRules) -> JumpTable {
+        let height = map.len();
+        let width = map[0].len();
+        let mut table = JumpTable {
+            width,
+            stops: vec![None; width * height * 4],
+        };
+
+        for x in 0..width {
+            // walking up, the stop is right below the last obstacle seen from the top
+            let mut stop: Option<Position> = None;
+            for y in 0..height {
+                if rules.is_blocking(map[y][x]) {
+                    stop = Some((x, y + 1));
+                } else {
+                    table.set(x, y, Perspective::Up, stop);
+                }
+            }
+
+            let mut stop: Option<Position> = None;
+            for y in (0..height).rev() {
+                if rules.is_blocking(map[y][x]) {
+                    stop = y.checked_sub(1).map(|y| (x, y));
+                } else {
+                    table.set(x, y, Perspective::Down, stop);
+                }
+            }
+        }
+
+        for y in 0..height {
+            let mut stop: Option<Position> = None;
+            for x in 0..width {
+                if rules.is_blocking(map[y][x]) {
+                    stop = Some((x + 1, y));
+                } else {
+                    table.set(x, y, Perspective::Left, stop);
+                }
+            }
+
+            let mut stop: Option<Position> = None;
+            for x in (0..width).rev() {
+                if rules.is_blocking(map[y][x]) {
+                    stop = x.checked_sub(1).map(|x| (x, y));
+                } else {
+                    table.set(x, y, Perspective::Right, stop);
+                }
+            }
+        }
+
+        return table;
+    }
+
+    fn set(&mut self, x: usize, y: usize, perspective: Perspective, stop: Option<Position>) {
+        self.stops[(y * self.width + x) * 4 + perspective_index(&perspective)] = stop;
+    }
+
+    // like `next_stop`, but also respecting one additionally placed obstacle
+    pub fn next_stop_with_obstacle(
+        &self,
+        (x, y): Position,
+        perspective: Perspective,
+        (obstacle_x, obstacle_y): Position,
+    ) -> Option<Position> {
+        let stop = self.stops[(y * self.width + x) * 4 + perspective_index(&perspective)];
+
+        return match perspective {
+            Perspective::Up if obstacle_x == x && obstacle_y < y => match stop {
+                Some((_, stop_y)) if stop_y > obstacle_y => stop,
+                _ => Some((x, obstacle_y + 1)),
+            },
+            Perspective::Down if obstacle_x == x && obstacle_y > y => match stop {
+                Some((_, stop_y)) if stop_y < obstacle_y => stop,
+                _ => Some((x, obstacle_y - 1)),
+            },
+            Perspective::Left if obstacle_y == y && obstacle_x < x => match stop {
+                Some((stop_x, _)) if stop_x > obstacle_x => stop,
+                _ => Some((obstacle_x + 1, y)),
+            },
+            Perspective::Right if obstacle_y == y && obstacle_x > x => match stop {
+                Some((stop_x, _)) if stop_x < obstacle_x => stop,
+                _ => Some((obstacle_x - 1, y)),
+            },
+            _ => stop,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JumpTable;
+    use crate::guard_avoidance_1::{parse_map, Perspective};
+    use crate::guard_rules::GuardRules;
+
+    #[test]
+    fn test_next_stop_with_obstacle() {
+        let map = parse_map(
+            "....#.....
+             .........#
+             ..........
+             ..#.......
+             .......#..
+             ..........
+             .#..^.....
+             ........#.
+             #.........
+             ......#...",
+        );
+        let table = JumpTable::new(&map, &GuardRules::default());
+        let far_away = (0, 9);
+
+        assert_eq!(
+            table.next_stop_with_obstacle((4, 6), Perspective::Up, far_away),
+            Some((4, 1))
+        );
+        assert_eq!(
+            table.next_stop_with_obstacle((4, 1), Perspective::Right, far_away),
+            Some((8, 1))
+        );
+        assert_eq!(
+            table.next_stop_with_obstacle((7, 7), Perspective::Down, far_away),
+            None
+        );
+        assert_eq!(
+            table.next_stop_with_obstacle((5, 6), Perspective::Left, far_away),
+            Some((2, 6))
+        );
+
+        // the placed obstacle is closer than the next one on the map
+        assert_eq!(
+            table.next_stop_with_obstacle((4, 6), Perspective::Up, (4, 3)),
+            Some((4, 4))
+        );
+        // the placed obstacle is behind the next one on the map
+        assert_eq!(
+            table.next_stop_with_obstacle((5, 6), Perspective::Left, (0, 6)),
+            Some((2, 6))
+        );
+        // the placed obstacle stops the guard from leaving the map
+        assert_eq!(
+            table.next_stop_with_obstacle((7, 7), Perspective::Down, (7, 9)),
+            Some((7, 8))
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::guard_avoidance_1::{rotate, Location, Map, Perspective, Position};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Turn {
+    Right,
+    Left,
+    // starts turning right, then left, then right again...
+    Alternating,
+}
+
+// How the guards behave, the default rules are the ones of the puzzle. They are followed by
+// `predict_next_step`, so every walk and loop finder knows them.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GuardRules {
+    pub turn: Turn,
+    // fields the guards can't walk onto, a placed obstacle `O` is always blocking
+    pub blocking: Vec<char>,
+    // guards leaving the map on one side come back on the opposite side
+    pub wrap_around: bool,
+}
+
+impl Default for GuardRules {
+    fn default() -> GuardRules {
+        return GuardRules {
+            turn: Turn::Right,
+            blocking: vec!['#'],
+            wrap_around: false,
+        };
+    }
+}
+
+fn turn_left(perspective: &Perspective) -> Perspective {
+    return match perspective {
+        Perspective::Up => Perspective::Left,
+        Perspective::Left => Perspective::Down,
+        Perspective::Down => Perspective::Right,
+        Perspective::Right => Perspective::Up,
+    };
+}
+
+impl GuardRules {
+    pub fn is_blocking(&self, field: char) -> bool {
+        return field == 'O' || self.blocking.contains(&field);
+    }
+
+    // the perspective of a guard turning in front of a blocking field after `turns` turns so far
+    pub fn turn(&self, perspective: Perspective, turns: usize) -> Perspective {
+        return match self.turn {
+            Turn::Right => rotate(&perspective),
+            Turn::Left => turn_left(&perspective),
+            Turn::Alternating if turns.is_multiple_of(2) => rotate(&perspective),
+            Turn::Alternating => turn_left(&perspective),
+        };
+    }
+
+    // the field in front of the guard, `None` if the guard leaves the map
+    pub fn ahead(&self, map: &Map, (x, y, perspective): Location) -> Option<Position> {
+        let height = map.len();
+        let width = map[0].len();
+
+        let (x, y) = match perspective {
+            Perspective::Up => (Some(x), y.checked_sub(1)),
+            Perspective::Down => (Some(x), Some(y + 1).filter(|y| *y < height)),
+            Perspective::Left => (x.checked_sub(1), Some(y)),
+            Perspective::Right => (Some(x + 1).filter(|x| *x < width), Some(y)),
+        };
+
+        if !self.wrap_around {
+            return Some((x?, y?));
+        }
+
+        return match perspective {
+            Perspective::Up => Some((x?, y.unwrap_or(height - 1))),
+            Perspective::Down => Some((x?, y.unwrap_or(0))),
+            Perspective::Left => Some((x.unwrap_or(width - 1), y?)),
+            Perspective::Right => Some((x.unwrap_or(0), y?)),
+        };
+    }
+
+    // what else has to repeat besides the location for a guard to be in a loop
+    pub fn phase(&self, turns: usize) -> usize {
+        return match self.turn {
+            Turn::Alternating => turns % 2,
+            _ => 0,
+        };
+    }
+}
+
+// ###############################################################################################################
+// ###############################################################################################################
+// # TESTS                                                                                                       #
+// ###############################################################################################################
+// ###############################################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::{GuardRules, Turn};
+    use crate::guard_avoidance_1::{
+        find_guards, parse_map, predict_path_positions, Perspective, Position,
+    };
+    use crate::guard_avoidance_2::{find_loops, LoopFinder};
+
+    const EXAMPLE_MAP: &str = "....#.....
+             .........#
+             ..........
+             ..#.......
+             .......#..
+             ..........
+             .#..^.....
+             ........#.
+             #.........
+             ......#...";
+
+    fn obstacles(map: &str, rules: &GuardRules) -> Vec<Position> {
+        return find_loops(&parse_map(map), rules, LoopFinder::Fast, None)
+            .iter()
+            .map(|found_loop| found_loop.obstacle)
+            .collect();
+    }
+
+    #[test]
+    fn test_finders_agree_with_rules() {
+        let map = parse_map(EXAMPLE_MAP);
+
+        for turn in [Turn::Right, Turn::Left, Turn::Alternating] {
+            let rules = GuardRules {
+                turn,
+                ..GuardRules::default()
+            };
+
+            let tracing = find_loops(&map, &rules, LoopFinder::Tracing, None);
+            let fast = find_loops(&map, &rules, LoopFinder::Fast, None);
+            assert_eq!(tracing, fast);
+        }
+    }
+
+    #[test]
+    fn test_turning_left() {
+        let rules = GuardRules {
+            turn: Turn::Left,
+            ..GuardRules::default()
+        };
+        let map = parse_map(
+            ".#....
+             ......
+             .^....",
+        );
+
+        // up, left in front of the obstacle and out of the map
+        let (count, steps) = predict_path_positions(&map, &rules);
+        assert_eq!(count, 3);
+        assert_eq!(steps, vec![(1, 2), (1, 1), (0, 1)]);
+    }
+
+    #[test]
+    fn test_alternating_turns() {
+        let rules = GuardRules {
+            turn: Turn::Alternating,
+            ..GuardRules::default()
+        };
+        let map = parse_map(
+            ".#....
+             ....#.
+             .^....",
+        );
+
+        // up, right until the obstacle, left again and up out of the map
+        let (_, steps) = predict_path_positions(&map, &rules);
+        assert_eq!(steps, vec![(1, 2), (1, 1), (2, 1), (3, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn test_additional_blocking_fields() {
+        let rules = GuardRules {
+            blocking: vec!['#', '~'],
+            ..GuardRules::default()
+        };
+        let map = parse_map(
+            ".~...
+             .^...",
+        );
+
+        let (_, steps) = predict_path_positions(&map, &rules);
+        assert_eq!(steps, vec![(1, 1), (2, 1), (3, 1), (4, 1)]);
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let rules = GuardRules {
+            wrap_around: true,
+            ..GuardRules::default()
+        };
+
+        // without any obstacle the guard walks up the same column forever
+        let map = parse_map(
+            "...
+             .^.
+             ...",
+        );
+        let (_, steps) = predict_path_positions(&map, &rules);
+        assert_eq!(steps, vec![(1, 1), (1, 0), (1, 2)]);
+
+        // the guard can't leave the map anymore, so no obstacle makes a difference
+        let loops = find_loops(&map, &rules, LoopFinder::Fast, None);
+        assert_eq!(loops.len(), 0);
+
+        // walking down, the guard comes back at the top right in front of the obstacle
+        let map = parse_map(
+            ".#.
+             .^#
+             ...",
+        );
+        let (_, steps) = predict_path_positions(&map, &rules);
+        assert_eq!(steps, vec![(1, 1), (1, 2), (0, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_multiple_guards() {
+        let map = "....#.....
+             .........#
+             ..........
+             ..#.......
+             .......#..
+             ..........
+             .#..^.....
+             ........#.
+             #.........
+             ......#..<";
+        assert_eq!(
+            find_guards(&parse_map(map)),
+            vec![(4, 6, Perspective::Up), (9, 9, Perspective::Left)]
+        );
+
+        let rules = GuardRules::default();
+        let (count, _) = predict_path_positions(&parse_map(map), &rules);
+        // the second guard walks up to the first guards way and leaves the map on the right
+        assert_eq!(count, 45);
+
+        // trapping one of the guards is enough, the way of the second guard doesn't add more
+        let single_guard = obstacles(EXAMPLE_MAP, &rules);
+        let both_guards = obstacles(map, &rules);
+        assert_eq!(both_guards, single_guard);
+    }
+}
@@ -1,23 +1,171 @@
-use std::fs;
+use std::env;
+use std::fs::{self, File};
 mod guard_avoidance_1;
-use guard_avoidance_1::{parse_map, predict_path_positions, show_map, Map};
+use guard_avoidance_1::{
+    draw_path, find_guard, parse_map, predict_path_positions, show_map, Guard, Map, Position,
+};
 
 mod guard_avoidance_2;
-use guard_avoidance_2::find_loops;
+use guard_avoidance_2::{
+    draw_loop, draw_obstacles, find_loops, trace_walk, Loop, LoopFinder, Progress,
+};
+
+mod export;
+use export::{write_gif, write_png};
+
+mod viewer;
+
+mod guard_rules;
+use guard_rules::{GuardRules, Turn};
+
+// `cargo run -r -- --turn left --blocking ~ --wrap-around` changes how the guards behave
+fn parse_rules(args: &Vec<String>) -> Result<GuardRules, String> {
+    let mut rules = GuardRules::default();
+
+    for (index, arg) in args.iter().enumerate() {
+        let value = args.get(index + 1);
+
+        match arg.as_str() {
+            "--turn" => {
+                rules.turn = match value.map(|value| value.as_str()) {
+                    Some("right") => Turn::Right,
+                    Some("left") => Turn::Left,
+                    Some("alternating") => Turn::Alternating,
+                    _ => return Err("guards can only turn right, left or alternating".to_string()),
+                }
+            }
+            "--blocking" => match value {
+                Some(fields) => rules.blocking.extend(fields.chars()),
+                None => return Err("blocking fields are missing".to_string()),
+            },
+            "--wrap-around" => rules.wrap_around = true,
+            _ => {}
+        }
+    }
+
+    return Ok(rules);
+}
+
+// `cargo run -r -- --export` writes the original path, all found loops and an animation of
+// the guard walking to `./export`
+fn export_images(
+    map: &Map,
+    rules: &GuardRules,
+    steps: &Vec<Position>,
+    loops: &Vec<Loop>,
+) -> Result<(), String> {
+    fs::create_dir_all("./export").map_err(|e| e.to_string())?;
+    let create = |name: &str| File::create(format!("./export/{}", name)).map_err(|e| e.to_string());
+
+    let start = Guard::new(find_guard(&map).unwrap());
+    let (_, original_path) = trace_walk(map.clone(), rules, start, &mut |_| {});
+    write_png(&original_path, 4, create("original_path.png")?)?;
+
+    let mut all_loops = draw_path(&map, &steps);
+    for found_loop in loops {
+        let (x, y) = found_loop.obstacle;
+        all_loops[y][x] = 'O';
+    }
+    write_png(&all_loops, 4, create("loops.png")?)?;
+
+    if let Some(found_loop) = loops.first() {
+        let cycle = found_loop.cycle(&map, &rules)?;
+        write_png(
+            &draw_loop(&map, found_loop, &cycle),
+            4,
+            create("first_loop.png")?,
+        )?;
+    }
+
+    write_gif(&map, rules, 4, 25, create("walk.gif")?)?;
+
+    return Ok(());
+}
 
 fn main() {
     let original_map =
         fs::read_to_string("./data/map.txt").expect("Should have been able to read the file");
-    let mut map: Map = parse_map(&original_map);
+    let map: Map = parse_map(&original_map);
+
+    let args: Vec<String> = env::args().collect();
+    let rules = match parse_rules(&args) {
+        Ok(rules) => rules,
+        Err(error) => {
+            eprintln!("{}", error);
+            eprintln!(
+                "usage: --turn right|left|alternating --blocking <fields, e.g. ~> --wrap-around"
+            );
+            return;
+        }
+    };
+
+    // `cargo run -r -- --view` steps through the walk of the guard in the terminal
+    if env::args().any(|arg| arg == "--view") {
+        viewer::run(&map, &rules).unwrap();
+        return;
+    }
+
+    // `cargo run -r -- --tracing` draws the maps while searching, which is a lot slower
+    let finder = if env::args().any(|arg| arg == "--tracing") {
+        LoopFinder::Tracing
+    } else {
+        LoopFinder::Fast
+    };
+
+    let (step_count, steps) = predict_path_positions(&map, &rules);
+    println!("guard will visit {} positions on its way", step_count);
+
+    if finder == LoopFinder::Tracing {
+        println!(
+            "Original Guards Path:\n{}\n",
+            show_map(&draw_path(&map, &steps))
+        );
+    }
+
+    // `cargo run -r -- --progress` shows how many obstacles have been checked so far
+    let show_progress = |checked: usize, total: usize| {
+        eprint!("\rchecked {} / {} obstacles", checked, total);
+    };
+    let progress: Option<Progress> = if env::args().any(|arg| arg == "--progress") {
+        Some(&show_progress)
+    } else {
+        None
+    };
+
+    let loops = find_loops(&map, &rules, finder, progress);
+    if progress.is_some() {
+        eprintln!();
+    }
+
+    // all tried obstacles, the ones trapping the guard in a loop marked with `O`
+    if finder == LoopFinder::Tracing {
+        println!(
+            "\n\n{}\n\n",
+            show_map(&draw_obstacles(&map, &steps, &loops))
+        );
+    }
+
+    // `cargo run -r -- --loops` draws every found loop together with its obstacle
+    if env::args().any(|arg| arg == "--loops") {
+        for found_loop in &loops {
+            let (x, y) = found_loop.obstacle;
 
-    let (original_guards_path, _, steps) = predict_path_positions(&mut map);
-    println!("guard will visit {} positions on its way", steps);
-    println!(
-        "Original Guards Path:\n{}\n",
-        show_map(&original_guards_path)
-    );
+            match found_loop.cycle(&map, &rules) {
+                Ok(cycle) => println!(
+                    "obstacle at line: {:#03} / col: {:#03} traps the guard in {} steps:\n{}\n",
+                    y,
+                    x,
+                    cycle.len(),
+                    show_map(&draw_loop(&map, found_loop, &cycle))
+                ),
+                Err(error) => eprintln!("{}", error),
+            }
+        }
+    }
 
-    let loops = find_loops(&map, &original_guards_path);
+    if env::args().any(|arg| arg == "--export") {
+        export_images(&map, &rules, &steps, &loops).unwrap();
+    }
 
-    println!("found {} possible loops", loops)
+    println!("found {} possible loops", loops.len())
 }
@@ -0,0 +1,289 @@
+use std::io::{self, Write};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::{cursor, execute, queue, style, terminal};
+
+use crate::guard_avoidance_1::{
+    find_guard, perspective_char, walk, Guard, Location, Map, Position,
+};
+use crate::guard_avoidance_2::draw_way;
+use crate::guard_rules::GuardRules;
+
+// The walk of the guard, stepped through one `predict_next_step` at a time. A candidate
+// obstacle can be toggled at the cursor, which immediately replaces the walk.
+pub struct Viewer {
+    map: Map,
+    rules: GuardRules,
+    obstacle: Option<Position>,
+    walked: Vec<Location>,
+    is_loop: bool,
+    step: usize,
+    cursor: Position,
+}
+
+// the walk of the guard on the map and whether it ends up in a loop
+fn walk_guard(map: &Map, rules: &GuardRules) -> (Vec<Location>, bool) {
+    let (walked, loop_start) = walk(&map, &rules, Guard::new(find_guard(&map).unwrap()));
+    return (walked, loop_start.is_some());
+}
+
+impl Viewer {
+    pub fn new(map: &Map, rules: &GuardRules) -> Viewer {
+        let (walked, is_loop) = walk_guard(&map, &rules);
+        let (x, y, _) = walked[0];
+
+        return Viewer {
+            map: map.clone(),
+            rules: rules.clone(),
+            obstacle: None,
+            walked,
+            is_loop,
+            step: 0,
+            cursor: (x, y),
+        };
+    }
+
+    fn map_with_obstacle(&self) -> Map {
+        let mut map = self.map.clone();
+
+        if let Some((x, y)) = self.obstacle {
+            map[y][x] = 'O';
+        }
+        return map;
+    }
+
+    pub fn step_forward(&mut self) {
+        self.step = (self.step + 1).min(self.walked.len() - 1);
+    }
+
+    pub fn step_backward(&mut self) {
+        self.step = self.step.saturating_sub(1);
+    }
+
+    pub fn move_cursor(&mut self, dx: isize, dy: isize) {
+        let (x, y) = self.cursor;
+        let x = x.saturating_add_signed(dx).min(self.map[0].len() - 1);
+        let y = y.saturating_add_signed(dy).min(self.map.len() - 1);
+
+        self.cursor = (x, y);
+    }
+
+    // Places the obstacle at the cursor or removes it again. Neither the guard's start nor
+    // an original obstacle can be replaced. The walk is kept up to the current step if possible.
+    pub fn toggle_obstacle(&mut self) {
+        let (x, y) = self.cursor;
+        let (guard_x, guard_y, _) = self.walked[0];
+
+        if self.obstacle == Some(self.cursor) {
+            self.obstacle = None;
+        } else if (x, y) != (guard_x, guard_y) && !self.rules.is_blocking(self.map[y][x]) {
+            self.obstacle = Some(self.cursor);
+        } else {
+            return;
+        }
+
+        (self.walked, self.is_loop) = walk_guard(&self.map_with_obstacle(), &self.rules);
+        self.step = self.step.min(self.walked.len() - 1);
+    }
+
+    // the map with the way of the guard up to the current step
+    pub fn render(&self) -> Map {
+        let mut map = self.map_with_obstacle();
+        let (x, y, _) = self.walked[0];
+        map[y][x] = '.';
+
+        draw_way(&mut map, &self.walked[..self.step]);
+
+        let (x, y, perspective) = self.walked[self.step];
+        map[y][x] = perspective_char(&perspective);
+        return map;
+    }
+
+    pub fn status(&self) -> String {
+        let (x, y) = self.cursor;
+        let outcome = match (self.obstacle, self.is_loop) {
+            (Some(_), true) => "the obstacle traps the guard in a loop",
+            (Some(_), false) => "the guard leaves the map despite the obstacle",
+            (None, true) => "the guard is in a loop",
+            (None, false) => "the guard leaves the map",
+        };
+
+        return format!(
+            "step {} / {} | cursor line: {:#03} / col: {:#03} | {}",
+            self.step,
+            self.walked.len() - 1,
+            y,
+            x,
+            outcome
+        );
+    }
+}
+
+// the part of the map around the cursor which fits into the terminal
+fn draw(viewer: &Viewer, out: &mut impl Write) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let map = viewer.render();
+
+    let width = (columns as usize).min(map[0].len());
+    let height = (rows as usize).saturating_sub(2).min(map.len());
+    let (cursor_x, cursor_y) = viewer.cursor;
+    let left = cursor_x.saturating_sub(width / 2).min(map[0].len() - width);
+    let top = cursor_y.saturating_sub(height / 2).min(map.len() - height);
+
+    queue!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    for (row, line) in map[top..top + height].iter().enumerate() {
+        let line: String = line[left..left + width].iter().collect();
+        queue!(out, cursor::MoveTo(0, row as u16), style::Print(line))?;
+    }
+
+    queue!(
+        out,
+        cursor::MoveTo(0, height as u16),
+        style::Print(viewer.status()),
+        cursor::MoveTo(0, height as u16 + 1),
+        style::Print("n/space: step, b: back, arrows: cursor, o: toggle obstacle, q: quit"),
+        cursor::MoveTo((cursor_x - left) as u16, (cursor_y - top) as u16)
+    )?;
+
+    return out.flush();
+}
+
+// Raw mode and the alternate screen for as long as it lives. Dropping it restores the terminal,
+// also when the viewer stops early because of an error.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new(out: &mut impl Write) -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        let guard = TerminalGuard;
+
+        execute!(out, terminal::EnterAlternateScreen, cursor::Show)?;
+        return Ok(guard);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // nothing left to report errors to while restoring
+        let _ = execute!(io::stdout(), terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn run(map: &Map, rules: &GuardRules) -> io::Result<()> {
+    let mut viewer = Viewer::new(map, rules);
+    let mut out = io::stdout();
+    let _terminal = TerminalGuard::new(&mut out)?;
+
+    loop {
+        draw(&viewer, &mut out)?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('n') | KeyCode::Char(' ') => viewer.step_forward(),
+                KeyCode::Char('b') | KeyCode::Backspace => viewer.step_backward(),
+                KeyCode::Char('o') => viewer.toggle_obstacle(),
+                KeyCode::Up => viewer.move_cursor(0, -1),
+                KeyCode::Down => viewer.move_cursor(0, 1),
+                KeyCode::Left => viewer.move_cursor(-1, 0),
+                KeyCode::Right => viewer.move_cursor(1, 0),
+                _ => {}
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+// ###############################################################################################################
+// ###############################################################################################################
+// # TESTS                                                                                                       #
+// ###############################################################################################################
+// ###############################################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::Viewer;
+    use crate::guard_avoidance_1::{parse_map, show_map};
+    use crate::guard_rules::GuardRules;
+
+    const EXAMPLE_MAP: &str = "....#.....
+             .........#
+             ..........
+             ..#.......
+             .......#..
+             ..........
+             .#..^.....
+             ........#.
+             #.........
+             ......#...";
+
+    #[test]
+    fn test_stepping() {
+        let mut viewer = Viewer::new(&parse_map(EXAMPLE_MAP), &GuardRules::default());
+
+        viewer.step_backward();
+        assert!(viewer.status().starts_with("step 0 / 54"));
+
+        for _ in 0..6 {
+            viewer.step_forward();
+        }
+        assert_eq!(
+            show_map(&viewer.render()),
+            "....#.....
+....>....#
+....|.....
+..#.|.....
+....|..#..
+....|.....
+.#..|.....
+........#.
+#.........
+......#..."
+        );
+
+        viewer.step_backward();
+        assert_eq!(viewer.render()[1][4], '^');
+
+        for _ in 0..100 {
+            viewer.step_forward();
+        }
+        assert!(viewer.status().starts_with("step 54 / 54"));
+        assert!(viewer.status().ends_with("the guard leaves the map"));
+    }
+
+    #[test]
+    fn test_toggle_obstacle() {
+        let mut viewer = Viewer::new(&parse_map(EXAMPLE_MAP), &GuardRules::default());
+
+        // the guard's start can't be blocked
+        viewer.toggle_obstacle();
+        assert!(viewer.status().ends_with("the guard leaves the map"));
+
+        viewer.move_cursor(-1, 0);
+        viewer.toggle_obstacle();
+        assert_eq!(viewer.render()[6][3], 'O');
+        assert_eq!(
+            viewer.status(),
+            "step 0 / 21 | cursor line: 006 / col: 003 | the obstacle traps the guard in a loop"
+        );
+
+        viewer.toggle_obstacle();
+        assert_eq!(viewer.render()[6][3], '.');
+        assert!(viewer.status().ends_with("the guard leaves the map"));
+    }
+
+    #[test]
+    fn test_move_cursor() {
+        let mut viewer = Viewer::new(&parse_map(EXAMPLE_MAP), &GuardRules::default());
+
+        viewer.move_cursor(-10, 20);
+        assert!(viewer.status().contains("cursor line: 009 / col: 000"));
+    }
+}
@@ -1,10 +1,21 @@
+use std::fmt;
+
 mod util;
 pub use util::parse;
+#[cfg(feature = "bigint")]
+pub use util::parse_as;
+
+mod number;
+pub use number::Number;
+use number::{concatenate, concatenation_factor};
+
+mod evaluation;
+pub use evaluation::{calibrate_with, parenthesise, Evaluation};
 
 #[derive(Debug, PartialEq)]
-pub struct Equation {
-    result: i64,
-    operands: Vec<i64>,
+pub struct Equation<T = i64> {
+    result: T,
+    operands: Vec<T>,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -12,95 +23,280 @@ pub enum Operator {
     Add,
     Multiply,
     Concatenate,
+    Subtract,
+    // integer division, rounding towards zero
+    Divide,
+    Exponent,
 }
 
-fn operator_by_index(i: i8) -> Operator {
-    return match i {
-        0 => Operator::Add,
-        1 => Operator::Multiply,
-        2 => Operator::Concatenate,
-        _ => panic!("unknown operator"),
-    };
+pub const PART_ONE_OPERATORS: [Operator; 2] = [Operator::Add, Operator::Multiply];
+pub const PART_TWO_OPERATORS: [Operator; 3] =
+    [Operator::Add, Operator::Multiply, Operator::Concatenate];
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Multiply => "*",
+            Operator::Concatenate => "||",
+            Operator::Subtract => "-",
+            Operator::Divide => "/",
+            Operator::Exponent => "^",
+        };
+        return write!(f, "{}", symbol);
+    }
 }
 
-fn increment(permutation: &Vec<i8>, base: i8, position: usize) -> Option<Vec<i8>> {
-    let mut permutation = permutation.clone();
+pub fn parse_operator(symbol: &str) -> Result<Operator, String> {
+    return match symbol {
+        "+" => Ok(Operator::Add),
+        "*" => Ok(Operator::Multiply),
+        "||" => Ok(Operator::Concatenate),
+        "-" => Ok(Operator::Subtract),
+        "/" => Ok(Operator::Divide),
+        "^" => Ok(Operator::Exponent),
+        _ => Err(format!("unknown operator '{}'", symbol)),
+    };
+}
 
-    let update = permutation[position] + 1;
+// `None` if the operator is not defined for the operands, e.g. dividing by 0, or overflows.
+// `target` is the result of the equation the operator is applied for.
+fn apply<T: Number>(operator: &Operator, a: &T, b: &T, target: &T) -> Option<T> {
+    return match operator {
+        Operator::Add => a.checked_add(b),
+        Operator::Multiply => a.checked_mul(b),
+        Operator::Concatenate => concatenate(a, b),
+        Operator::Subtract => a.checked_sub(b),
+        Operator::Divide => a.checked_div(b),
+        Operator::Exponent => a.checked_pow(b, target),
+    };
+}
 
-    if update <= (base - 1) {
-        permutation[position] = update;
-        return Some(permutation);
-    } else if position > 0 {
-        // handle overflow
-        let next_position = position - 1;
+// the value left of the last operand, so that applying the operator to it gives `result`
+fn undo<T: Number>(operator: &Operator, result: &T, b: &T) -> Option<T> {
+    return match operator {
+        Operator::Add if result >= b => result.checked_sub(b),
+        Operator::Multiply if result.checked_rem(b)? == T::from_i64(0) => result.checked_div(b),
+        Operator::Concatenate if result >= b => {
+            let factor = concatenation_factor(b)?;
 
-        // reset lower indexes
-        for (i, _) in permutation[position..].to_vec().iter().enumerate() {
-            permutation[i + position] = 0;
+            if result.checked_rem(&factor)? == *b {
+                result.checked_div(&factor)
+            } else {
+                None
+            }
         }
+        _ => None,
+    };
+}
+
+// All operator assignments satisfying an equation, the first one found is kept. With
+// `first_only` the search stops right there, as only counting needs to go on.
+struct Search<'a, T> {
+    operators: &'a Vec<Operator>,
+    target: &'a T,
+    first_only: bool,
+    first: Option<Vec<Operator>>,
+    count: u64,
+}
 
-        return increment(&permutation, base, next_position);
+impl<'a, T> Search<'a, T> {
+    fn new(operators: &'a Vec<Operator>, target: &'a T, first_only: bool) -> Search<'a, T> {
+        return Search {
+            operators,
+            target,
+            first_only,
+            first: None,
+            count: 0,
+        };
     }
 
-    return None;
+    // `count` assignments satisfy the equation, `operators` is the first of them
+    fn found(&mut self, operators: Vec<Operator>, count: u64) {
+        self.count = self.count.saturating_add(count);
+
+        if self.first.is_none() {
+            self.first = Some(operators);
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        return self.first_only && self.first.is_some();
+    }
 }
 
-fn compute_result_for_permutation(operands: &Vec<i64>, permutation: &Vec<i8>) -> i64 {
-    let mut result = 0;
+// Searches from right to left: the last operator has to be one which can be undone for the last
+// operand, branches where no operator fits are dropped right away. `chosen` are the operators
+// right of `operands`, from right to left.
+fn search_backwards<T: Number>(
+    search: &mut Search<T>,
+    result: &T,
+    operands: &[T],
+    chosen: &mut Vec<Operator>,
+) {
+    let Some((b, rest)) = operands.split_last() else {
+        return;
+    };
 
-    for i in 0..operands.len() - 1 {
-        let a = operands[i];
-        let b = operands[i + 1];
+    if rest.is_empty() {
+        if b == result {
+            search.found(chosen.iter().rev().copied().collect(), 1);
+        }
+        return;
+    }
 
-        if i == 0 {
-            result = a;
+    let zero = T::from_i64(0);
+
+    for operator in search.operators {
+        if search.is_done() {
+            return;
         }
-        let operator = operator_by_index(permutation[i]);
+        chosen.push(*operator);
+
+        if *operator == Operator::Multiply && *b == zero && *result == zero {
+            // a multiplication with 0 results in 0, no matter the operators before. So all their
+            // combinations count without trying them, the first one tried would be the first one.
+            let left = vec![search.operators[0]; rest.len() - 1];
+            let right: Vec<Operator> = chosen.iter().rev().copied().collect();
+            let combinations = (search.operators.len() as u64).saturating_pow(left.len() as u32);
 
-        result = match operator {
-            Operator::Add => result + b,
-            Operator::Multiply => result * b,
-            Operator::Concatenate => format!("{}{}", result, b).parse::<i64>().unwrap(),
+            search.found([left, right].concat(), combinations);
+        } else if let Some(result) = undo(operator, result, b) {
+            search_backwards(search, &result, rest, chosen);
         }
+
+        chosen.pop();
     }
+}
+
+// Tries all operators from left to right, for operators which can't be undone unambiguously
+// or operands which can get negative. `chosen` are the operators left of `value`.
+fn search_forward<T: Number>(
+    search: &mut Search<T>,
+    result: &T,
+    value: &T,
+    operands: &[T],
+    chosen: &mut Vec<Operator>,
+) {
+    let Some((b, rest)) = operands.split_first() else {
+        if result == value {
+            search.found(chosen.clone(), 1);
+        }
+        return;
+    };
 
-    return result;
+    for operator in search.operators {
+        if search.is_done() {
+            return;
+        }
+        if let Some(value) = apply(operator, value, b, search.target) {
+            chosen.push(*operator);
+            search_forward(search, result, &value, rest, chosen);
+            chosen.pop();
+        }
+    }
 }
 
-fn try_configuration(
-    Equation { operands, result }: &Equation,
-    operators: &Vec<Operator>,
-) -> Result<i64, String> {
-    let mut operator_permutation: Vec<i8> = vec![0; operands.len()];
+// Going backwards relies on the values never getting negative, which only holds for
+// non-negative operands combined by adding, multiplying and concatenating.
+fn can_be_searched_backwards<T: Number>(operands: &Vec<T>, operators: &Vec<Operator>) -> bool {
+    return operands.iter().all(|operand| *operand >= T::from_i64(0))
+        && operators
+            .iter()
+            .all(|operator| PART_TWO_OPERATORS.contains(operator));
+}
 
-    while let Some(perm) = increment(
-        &operator_permutation,
-        operators.len() as i8,
-        operands.len() - 1,
-    ) {
-        operator_permutation = perm;
+// a solved equation, rendered like `190 = 10 * 19`
+#[derive(Debug, PartialEq)]
+pub struct Solution<T = i64> {
+    pub result: T,
+    pub operands: Vec<T>,
+    // the first operators found which satisfy the equation
+    pub operators: Vec<Operator>,
+    // the number of all operator assignments satisfying the equation
+    pub count: u64,
+}
 
-        let config_result = compute_result_for_permutation(&operands, &operator_permutation);
+impl<T: Number> fmt::Display for Solution<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.result, self.operands[0])?;
 
-        if *result == config_result {
-            return Ok(config_result);
+        for (operator, operand) in self.operators.iter().zip(&self.operands[1..]) {
+            write!(f, " {} {}", operator, operand)?;
         }
+        return Ok(());
+    }
+}
+
+// With `first_only` the solution only knows the first satisfying assignment, its count is 1.
+fn find_solution<T: Number>(
+    Equation { operands, result }: &Equation<T>,
+    operators: &Vec<Operator>,
+    first_only: bool,
+) -> Result<Solution<T>, String> {
+    let mut search = Search::new(operators, result, first_only);
+
+    if can_be_searched_backwards(&operands, &operators) {
+        search_backwards(&mut search, result, &operands, &mut vec![]);
+    } else {
+        search_forward(
+            &mut search,
+            result,
+            &operands[0],
+            &operands[1..],
+            &mut vec![],
+        );
     }
 
-    return Err("failed to compute any results".to_string());
+    let Some(first) = search.first else {
+        return Err("failed to compute any results".to_string());
+    };
+
+    return Ok(Solution {
+        result: result.clone(),
+        operands: operands.clone(),
+        operators: first,
+        count: search.count,
+    });
 }
 
-pub fn calibrate(equations: Vec<Equation>) -> i64 {
-    let mut result = 0;
-    let operators = [Operator::Add, Operator::Multiply, Operator::Concatenate].to_vec();
+// the first satisfying assignment together with the number of all of them
+pub fn solve<T: Number>(
+    equation: &Equation<T>,
+    operators: &Vec<Operator>,
+) -> Result<Solution<T>, String> {
+    return find_solution(equation, operators, false);
+}
+
+// stops at the first satisfying assignment, the calibration doesn't need any more
+fn try_configuration<T: Number>(
+    equation: &Equation<T>,
+    operators: &Vec<Operator>,
+) -> Result<T, String> {
+    return find_solution(equation, operators, true).map(|solution| solution.result);
+}
+
+// all equations which can be solved with the operators
+pub fn solve_all<T: Number>(
+    equations: &Vec<Equation<T>>,
+    operators: &Vec<Operator>,
+) -> Vec<Solution<T>> {
+    return equations
+        .iter()
+        .filter_map(|equation| solve(equation, operators).ok())
+        .collect();
+}
+
+// `None` if the sum of all computable results overflows
+pub fn calibrate<T: Number>(equations: &Vec<Equation<T>>, operators: &Vec<Operator>) -> Option<T> {
+    let mut result = T::from_i64(0);
 
     for equation in equations {
         if let Ok(intermediante_result) = try_configuration(&equation, &operators) {
-            result += intermediante_result;
+            result = result.checked_add(&intermediante_result)?;
         }
     }
-    return result;
+    return Some(result);
 }
 
 // ###############################################################################################################
@@ -111,7 +307,10 @@ pub fn calibrate(equations: Vec<Equation>) -> i64 {
 
 #[cfg(test)]
 mod tests {
-    use crate::calibration::{calibrate, try_configuration, util::parse, Equation, Operator};
+    use crate::calibration::{
+        calibrate, parse_operator, solve, solve_all, try_configuration, undo, util::parse,
+        Equation, Operator, PART_ONE_OPERATORS, PART_TWO_OPERATORS,
+    };
 
     #[test]
     fn test_try_configuration() {
@@ -130,6 +329,46 @@ mod tests {
         let operators = [Operator::Add, Operator::Multiply].to_vec();
         let result = try_configuration(&equation, &operators).unwrap();
         assert_eq!(result, 3267);
+
+        let equation = Equation {
+            result: 7290,
+            operands: vec![6, 8, 6, 15],
+        };
+        assert!(try_configuration(&equation, &operators).is_err());
+
+        let operators = [Operator::Add, Operator::Multiply, Operator::Concatenate].to_vec();
+        let result = try_configuration(&equation, &operators).unwrap();
+        assert_eq!(result, 7290);
+    }
+
+    #[test]
+    fn test_many_operands() {
+        // 3^24 combinations of operators, way too many to try them all, 2 * 2 * ... * 1 + 11 fits
+        let equation = Equation {
+            result: 2_i64.pow(20) + 11,
+            operands: [vec![2; 20], vec![1; 4], vec![11]].concat(),
+        };
+        let operators = [Operator::Add, Operator::Multiply, Operator::Concatenate].to_vec();
+        assert!(try_configuration(&equation, &operators).is_ok());
+
+        let equation = Equation {
+            result: 2_i64.pow(20) + 3,
+            operands: vec![2; 25],
+        };
+        assert!(try_configuration(&equation, &operators).is_err());
+    }
+
+    #[test]
+    fn test_undo() {
+        assert_eq!(undo(&Operator::Add, &190, &19), Some(171));
+        assert_eq!(undo(&Operator::Add, &10, &19), None);
+        assert_eq!(undo(&Operator::Multiply, &190, &19), Some(10));
+        assert_eq!(undo(&Operator::Multiply, &191, &19), None);
+        assert_eq!(undo(&Operator::Concatenate, &156, &6), Some(15));
+        assert_eq!(undo(&Operator::Concatenate, &1510, &10), Some(15));
+        assert_eq!(undo(&Operator::Concatenate, &156, &56), Some(1));
+        assert_eq!(undo(&Operator::Concatenate, &156, &156), Some(0));
+        assert_eq!(undo(&Operator::Concatenate, &156, &7), None);
     }
 
     #[test]
@@ -146,7 +385,170 @@ mod tests {
                         292: 11 6 16 20",
         );
 
-        let result = calibrate(equations);
-        assert_eq!(result, 3749)
+        // part one only adds and multiplies
+        let result = calibrate(&equations, &PART_ONE_OPERATORS.to_vec()).unwrap();
+        assert_eq!(result, 3749);
+
+        // part two concatenates as well, which solves three more equations
+        let result = calibrate(&equations, &PART_TWO_OPERATORS.to_vec()).unwrap();
+        assert_eq!(result, 11387);
+    }
+
+    #[test]
+    fn test_parse_operator() {
+        let operators: Vec<Operator> = ["+", "*", "||", "-", "/", "^"]
+            .iter()
+            .map(|symbol| parse_operator(symbol).unwrap())
+            .collect();
+        assert_eq!(operators.len(), 6);
+        assert_eq!(operators[2], Operator::Concatenate);
+
+        assert!(parse_operator("%").is_err());
+    }
+
+    #[test]
+    fn test_additional_operators() {
+        let equations = parse(
+            "5: 10 5
+             3: 10 3
+             1000: 10 3
+             7: 2 3 1
+             -4: 2 6
+             0: 5 0",
+        );
+
+        let operators = [Operator::Subtract].to_vec();
+        assert_eq!(calibrate(&equations, &operators).unwrap(), 5 - 4);
+
+        // 10 / 3 rounds down, dividing by 0 is never a solution
+        let operators = [Operator::Divide].to_vec();
+        assert_eq!(calibrate(&equations, &operators).unwrap(), 3);
+
+        // 2 ^ 3 - 1 = 7
+        let operators = [Operator::Exponent, Operator::Subtract].to_vec();
+        assert_eq!(calibrate(&equations, &operators).unwrap(), 5 + 1000 + 7 - 4);
+    }
+
+    #[test]
+    fn test_solve() {
+        let equations = parse(
+            "190: 10 19
+             3267: 81 40 27
+             83: 17 5
+             156: 15 6
+             7290: 6 8 6 15
+             161011: 16 10 13
+             192: 17 8 14
+             21037: 9 7 18 13
+             292: 11 6 16 20",
+        );
+
+        let solutions: Vec<String> = solve_all(&equations, &PART_ONE_OPERATORS.to_vec())
+            .iter()
+            .map(|solution| format!("{} ({})", solution, solution.count))
+            .collect();
+        assert_eq!(
+            solutions,
+            vec![
+                "190 = 10 * 19 (1)",
+                "3267 = 81 * 40 + 27 (2)",
+                "292 = 11 + 6 * 16 + 20 (1)"
+            ]
+        );
+
+        let solutions = solve_all(&equations, &PART_TWO_OPERATORS.to_vec());
+        assert_eq!(solutions.len(), 6);
+        assert_eq!(solutions[4].to_string(), "192 = 17 || 8 + 14");
+    }
+
+    #[test]
+    fn test_solve_with_zeros() {
+        // any operators in front of multiplying with 0
+        let equation = Equation {
+            result: 0,
+            operands: vec![3, 4, 0],
+        };
+        let solution = solve(&equation, &PART_ONE_OPERATORS.to_vec()).unwrap();
+        assert_eq!(solution.count, 2);
+        assert_eq!(solution.to_string(), "0 = 3 + 4 * 0");
+
+        let equation = Equation {
+            result: 7,
+            operands: vec![0, 7],
+        };
+        let solution = solve(&equation, &PART_TWO_OPERATORS.to_vec()).unwrap();
+        // 0 + 7 and 0 || 7
+        assert_eq!(solution.count, 2);
+
+        // going forward the operators are found the same way
+        let operators = [Operator::Add, Operator::Multiply, Operator::Subtract].to_vec();
+        let solution = solve(&equation, &operators).unwrap();
+        assert_eq!(solution.to_string(), "7 = 0 + 7");
+        assert_eq!(solution.count, 1);
+    }
+
+    #[test]
+    fn test_long_equation_ending_with_zero() {
+        let twos = vec!["2"; 22].join(" ");
+        let equations = parse(&format!("0: {} 0", twos));
+
+        // all combinations of the operators in front of the multiplication with 0
+        let solution = solve(&equations[0], &PART_ONE_OPERATORS.to_vec()).unwrap();
+        assert_eq!(solution.count, 2_u64.pow(21));
+        let solution = solve(&equations[0], &PART_TWO_OPERATORS.to_vec()).unwrap();
+        assert_eq!(solution.count, 3_u64.pow(21));
+
+        assert_eq!(calibrate(&equations, &PART_TWO_OPERATORS.to_vec()), Some(0));
+        assert_eq!(
+            try_configuration(&equations[0], &PART_ONE_OPERATORS.to_vec()),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // adding first overflows, which is just not the solution
+        let equation = Equation {
+            result: -1,
+            operands: vec![i64::MAX, 1, i64::MAX],
+        };
+        let operators = [Operator::Add, Operator::Subtract].to_vec();
+        assert_eq!(try_configuration(&equation, &operators), Ok(-1));
+
+        let equation = Equation {
+            result: i64::MAX,
+            operands: vec![9, 223372036854775807],
+        };
+        let operators = [Operator::Multiply, Operator::Concatenate].to_vec();
+        assert_eq!(try_configuration(&equation, &operators), Ok(i64::MAX));
+
+        // the sum of both results doesn't fit anymore
+        let equations = vec![
+            Equation {
+                result: i64::MAX,
+                operands: vec![i64::MAX],
+            },
+            Equation {
+                result: 1,
+                operands: vec![1],
+            },
+        ];
+        assert_eq!(calibrate(&equations, &operators), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_equations() {
+        use crate::calibration::util::parse_as;
+        use num_bigint::BigInt;
+
+        let equations = parse_as::<BigInt>(
+            "92233720368547758070: 9223372036854775807 10
+             922337203685477580710: 9223372036854775807 10",
+        )
+        .unwrap();
+
+        let result = calibrate(&equations, &PART_TWO_OPERATORS.to_vec()).unwrap();
+        assert_eq!(result, "1014570924054025338780".parse::<BigInt>().unwrap());
     }
 }
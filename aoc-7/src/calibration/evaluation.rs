@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{
+    apply, can_be_searched_backwards, try_configuration, Equation, Number, Operator, Search,
+    Solution,
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Evaluation {
+    // strictly from left to right, as the elephants do it
+    LeftToRight,
+    // `||` binds the tightest, then `^`, then `*` and `/`, then `+` and `-`, each from left to right
+    Precedence,
+    // any parenthesisation of the operands, evaluated from the innermost parentheses
+    Parenthesised,
+}
+
+fn precedence(operator: &Operator) -> u8 {
+    return match operator {
+        Operator::Add | Operator::Subtract => 1,
+        Operator::Multiply | Operator::Divide => 2,
+        Operator::Exponent => 3,
+        Operator::Concatenate => 4,
+    };
+}
+
+// The operands and operators which still wait for an operator of lower precedence, like
+// `1 + 2 * 3` before it is known whether a `*` or a `+` follows.
+#[derive(Clone)]
+struct PendingOperations<T> {
+    values: Vec<T>,
+    operators: Vec<Operator>,
+}
+
+impl<T: Number> PendingOperations<T> {
+    // applies all pending operators with at least the given precedence, `None` on overflow
+    fn reduce(&mut self, min_precedence: u8, target: &T) -> Option<()> {
+        while let Some(operator) = self.operators.last() {
+            if precedence(operator) < min_precedence {
+                break;
+            }
+
+            let operator = self.operators.pop().unwrap();
+            let b = self.values.pop().unwrap();
+            let a = self.values.pop().unwrap();
+            self.values.push(apply(&operator, &a, &b, target)?);
+        }
+        return Some(());
+    }
+
+    fn push(&mut self, operator: Operator, b: &T, target: &T) -> Option<()> {
+        self.reduce(precedence(&operator), target)?;
+        self.operators.push(operator);
+        self.values.push(b.clone());
+        return Some(());
+    }
+
+    fn result(mut self, target: &T) -> Option<T> {
+        self.reduce(0, target)?;
+        return self.values.pop();
+    }
+}
+
+// Tries all operators respecting their precedence. With the operators of part two and positive
+// operands no part of the expression can be bigger than the whole, so those branches get dropped.
+fn search_with_precedence<T: Number>(
+    search: &mut Search<T>,
+    result: &T,
+    pending: PendingOperations<T>,
+    operands: &[T],
+    chosen: &mut Vec<Operator>,
+    only_growing: bool,
+) {
+    if only_growing && pending.values.iter().any(|value| value > result) {
+        return;
+    }
+
+    let Some((b, rest)) = operands.split_first() else {
+        if pending.result(result).as_ref() == Some(result) {
+            search.found(chosen.clone(), 1);
+        }
+        return;
+    };
+
+    for operator in search.operators {
+        if search.is_done() {
+            return;
+        }
+        let mut pending = pending.clone();
+
+        if pending.push(*operator, b, result).is_some() {
+            chosen.push(*operator);
+            search_with_precedence(search, result, pending, rest, chosen, only_growing);
+            chosen.pop();
+        }
+    }
+}
+
+fn only_growing<T: Number>(operands: &Vec<T>, operators: &Vec<Operator>) -> bool {
+    return can_be_searched_backwards(operands, operators)
+        && operands.iter().all(|operand| *operand > T::from_i64(0));
+}
+
+// with `first_only` the search stops at the first satisfying assignment, like for `solve`
+fn solve_with_precedence<T: Number>(
+    Equation { operands, result }: &Equation<T>,
+    operators: &Vec<Operator>,
+    first_only: bool,
+) -> Result<Solution<T>, String> {
+    let mut search = Search::new(operators, result, first_only);
+    let pending = PendingOperations {
+        values: vec![operands[0].clone()],
+        operators: vec![],
+    };
+
+    search_with_precedence(
+        &mut search,
+        result,
+        pending,
+        &operands[1..],
+        &mut vec![],
+        only_growing(operands, operators),
+    );
+
+    let Some(first) = search.first else {
+        return Err("failed to compute any results".to_string());
+    };
+
+    return Ok(Solution {
+        result: result.clone(),
+        operands: operands.clone(),
+        operators: first,
+        count: search.count,
+    });
+}
+
+// a parenthesised expression, rendered with parentheses around every inner operation
+#[derive(Debug, PartialEq)]
+pub enum Expression<T> {
+    Operand(T),
+    Operation(Box<Expression<T>>, Operator, Box<Expression<T>>),
+}
+
+impl<T: Number> Expression<T> {
+    fn fmt_inner(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Expression::Operand(_) => write!(f, "{}", self),
+            Expression::Operation(..) => write!(f, "({})", self),
+        };
+    }
+}
+
+impl<T: Number> fmt::Display for Expression<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Expression::Operand(value) => write!(f, "{}", value),
+            Expression::Operation(a, operator, b) => {
+                a.fmt_inner(f)?;
+                write!(f, " {} ", operator)?;
+                b.fmt_inner(f)
+            }
+        };
+    }
+}
+
+// how often a value can be computed from a range of operands, and one way of doing so
+struct Computations<T> {
+    count: u64,
+    // the operands are split before index `split`, the left and right part give the values
+    last_operation: Option<(usize, Operator, T, T)>,
+}
+
+type ValuesOfRanges<T> = HashMap<(usize, usize), HashMap<T, Computations<T>>>;
+
+fn expression<T: Number>(
+    values: &ValuesOfRanges<T>,
+    (from, to): (usize, usize),
+    value: &T,
+) -> Expression<T> {
+    return match &values[&(from, to)][value].last_operation {
+        None => Expression::Operand(value.clone()),
+        Some((split, operator, a, b)) => Expression::Operation(
+            Box::new(expression(values, (from, *split), a)),
+            *operator,
+            Box::new(expression(values, (*split, to), b)),
+        ),
+    };
+}
+
+// Computes all values of all ranges of operands, from single operands up to all of them. Each
+// value remembers how many parenthesisations and operators lead to it.
+pub fn parenthesise<T: Number>(
+    Equation { operands, result }: &Equation<T>,
+    operators: &Vec<Operator>,
+) -> Result<(Expression<T>, u64), String> {
+    let only_growing = only_growing(operands, operators);
+    let mut values: ValuesOfRanges<T> = HashMap::new();
+
+    for (index, operand) in operands.iter().enumerate() {
+        let computations = Computations {
+            count: 1,
+            last_operation: None,
+        };
+        values.insert(
+            (index, index + 1),
+            HashMap::from([(operand.clone(), computations)]),
+        );
+    }
+
+    for length in 2..=operands.len() {
+        for from in 0..=operands.len() - length {
+            let to = from + length;
+            let mut range_values: HashMap<T, Computations<T>> = HashMap::new();
+
+            for split in from + 1..to {
+                for (a, left) in &values[&(from, split)] {
+                    for (b, right) in &values[&(split, to)] {
+                        for operator in operators {
+                            let Some(value) = apply(operator, a, b, result) else {
+                                continue;
+                            };
+                            if only_growing && value > *result {
+                                continue;
+                            }
+
+                            let count = left.count.saturating_mul(right.count);
+                            let computations = range_values.entry(value).or_insert(Computations {
+                                count: 0,
+                                last_operation: Some((split, *operator, a.clone(), b.clone())),
+                            });
+                            computations.count = computations.count.saturating_add(count);
+                        }
+                    }
+                }
+            }
+
+            values.insert((from, to), range_values);
+        }
+    }
+
+    let all = (0, operands.len());
+    let Some(computations) = values[&all].get(result) else {
+        return Err("failed to compute any results".to_string());
+    };
+
+    return Ok((expression(&values, all, result), computations.count));
+}
+
+pub fn solve_with<T: Number>(
+    equation: &Equation<T>,
+    operators: &Vec<Operator>,
+    evaluation: Evaluation,
+) -> Result<T, String> {
+    return match evaluation {
+        Evaluation::LeftToRight => try_configuration(equation, operators),
+        Evaluation::Precedence => {
+            solve_with_precedence(equation, operators, true).map(|solution| solution.result)
+        }
+        Evaluation::Parenthesised => {
+            parenthesise(equation, operators).map(|_| equation.result.clone())
+        }
+    };
+}
+
+// `None` if the sum of all computable results overflows
+pub fn calibrate_with<T: Number>(
+    equations: &Vec<Equation<T>>,
+    operators: &Vec<Operator>,
+    evaluation: Evaluation,
+) -> Option<T> {
+    let mut result = T::from_i64(0);
+
+    for equation in equations {
+        if let Ok(intermediante_result) = solve_with(&equation, &operators, evaluation) {
+            result = result.checked_add(&intermediante_result)?;
+        }
+    }
+    return Some(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{calibrate_with, parenthesise, solve_with_precedence, Evaluation};
+    use crate::calibration::{
+        util::parse, Equation, Operator, PART_ONE_OPERATORS, PART_TWO_OPERATORS,
+    };
+
+    const EXAMPLE_EQUATIONS: &str = "190: 10 19
+             3267: 81 40 27
+             83: 17 5
+             156: 15 6
+             7290: 6 8 6 15
+             161011: 16 10 13
+             192: 17 8 14
+             21037: 9 7 18 13
+             292: 11 6 16 20";
+
+    #[test]
+    fn test_left_to_right() {
+        let equations = parse(EXAMPLE_EQUATIONS);
+        let operators = PART_TWO_OPERATORS.to_vec();
+
+        assert_eq!(
+            calibrate_with(&equations, &operators, Evaluation::LeftToRight),
+            Some(11387)
+        );
+    }
+
+    #[test]
+    fn test_precedence() {
+        let operators = PART_ONE_OPERATORS.to_vec();
+
+        // 11 + 6 * 16 + 20 only works from left to right
+        let equation = Equation {
+            result: 292,
+            operands: vec![11, 6, 16, 20],
+        };
+        assert!(solve_with_precedence(&equation, &operators, false).is_err());
+
+        // 81 + 40 * 27 = 1161
+        let equation = Equation {
+            result: 1161,
+            operands: vec![81, 40, 27],
+        };
+        let solution = solve_with_precedence(&equation, &operators, false).unwrap();
+        assert_eq!(solution.to_string(), "1161 = 81 + 40 * 27");
+        assert_eq!(solution.count, 1);
+
+        // 2 + 3 || 4 = 36
+        let equation = Equation {
+            result: 36,
+            operands: vec![2, 3, 4],
+        };
+        let operators = PART_TWO_OPERATORS.to_vec();
+        assert!(solve_with_precedence(&equation, &operators, false).is_ok());
+
+        // 2 ^ 3 * 2 - 1 = 15 and 2 - 3 * 4 = -10
+        let operators = [Operator::Subtract, Operator::Multiply, Operator::Exponent].to_vec();
+        let equation = Equation {
+            result: 15,
+            operands: vec![2, 3, 2, 1],
+        };
+        assert!(solve_with_precedence(&equation, &operators, false).is_ok());
+        let equation = Equation {
+            result: -10,
+            operands: vec![2, 3, 4],
+        };
+        assert!(solve_with_precedence(&equation, &operators, false).is_ok());
+
+        let equations = parse(EXAMPLE_EQUATIONS);
+        assert_eq!(
+            calibrate_with(
+                &equations,
+                &PART_ONE_OPERATORS.to_vec(),
+                Evaluation::Precedence
+            ),
+            Some(190 + 3267)
+        );
+    }
+
+    #[test]
+    fn test_parenthesise() {
+        let operators = PART_ONE_OPERATORS.to_vec();
+
+        // 11 + 6 * 16 + 20 needs the first parentheses only
+        let equation = Equation {
+            result: 292,
+            operands: vec![11, 6, 16, 20],
+        };
+        let (expression, count) = parenthesise(&equation, &operators).unwrap();
+        assert_eq!(expression.to_string(), "((11 + 6) * 16) + 20");
+        assert_eq!(count, 1);
+
+        // 3 * (4 + 5) = 27
+        let equation = Equation {
+            result: 27,
+            operands: vec![3, 4, 5],
+        };
+        let (expression, _) = parenthesise(&equation, &operators).unwrap();
+        assert_eq!(expression.to_string(), "3 * (4 + 5)");
+
+        // (1 + 2) + 3 and 1 + (2 + 3), as well as both with `*`
+        let equation = Equation {
+            result: 6,
+            operands: vec![1, 2, 3],
+        };
+        let (_, count) = parenthesise(&equation, &operators).unwrap();
+        assert_eq!(count, 4);
+
+        let equations = parse(EXAMPLE_EQUATIONS);
+        let left_to_right =
+            calibrate_with(&equations, &operators, Evaluation::LeftToRight).unwrap();
+        let parenthesised =
+            calibrate_with(&equations, &operators, Evaluation::Parenthesised).unwrap();
+        assert!(parenthesised >= left_to_right);
+    }
+}
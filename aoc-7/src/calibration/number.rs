@@ -0,0 +1,189 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+// The arithmetic the calibration needs. Every operation returns `None` instead of
+// overflowing, so an overflowing combination of operators simply doesn't fit.
+pub trait Number: Clone + Eq + Hash + PartialOrd + Debug + Display + FromStr {
+    fn from_i64(value: i64) -> Self;
+    fn checked_add(&self, b: &Self) -> Option<Self>;
+    fn checked_sub(&self, b: &Self) -> Option<Self>;
+    fn checked_mul(&self, b: &Self) -> Option<Self>;
+    // rounds towards zero, `None` for a division by 0
+    fn checked_div(&self, b: &Self) -> Option<Self>;
+    fn checked_rem(&self, b: &Self) -> Option<Self>;
+    // `None` as well for powers way beyond the `target` of the calculation, for numbers which
+    // would rather grow than overflow
+    fn checked_pow(&self, exponent: &Self, target: &Self) -> Option<Self>;
+}
+
+impl Number for i64 {
+    fn from_i64(value: i64) -> i64 {
+        return value;
+    }
+
+    fn checked_add(&self, b: &i64) -> Option<i64> {
+        return i64::checked_add(*self, *b);
+    }
+
+    fn checked_sub(&self, b: &i64) -> Option<i64> {
+        return i64::checked_sub(*self, *b);
+    }
+
+    fn checked_mul(&self, b: &i64) -> Option<i64> {
+        return i64::checked_mul(*self, *b);
+    }
+
+    fn checked_div(&self, b: &i64) -> Option<i64> {
+        return i64::checked_div(*self, *b);
+    }
+
+    fn checked_rem(&self, b: &i64) -> Option<i64> {
+        return i64::checked_rem(*self, *b);
+    }
+
+    fn checked_pow(&self, exponent: &i64, _target: &i64) -> Option<i64> {
+        return i64::checked_pow(*self, u32::try_from(*exponent).ok()?);
+    }
+}
+
+// how many bits a power may exceed the target by, so dividing or subtracting can still reach it
+#[cfg(feature = "bigint")]
+const POWER_MARGIN_BITS: u64 = 64;
+
+#[cfg(feature = "bigint")]
+impl Number for num_bigint::BigInt {
+    fn from_i64(value: i64) -> num_bigint::BigInt {
+        return num_bigint::BigInt::from(value);
+    }
+
+    fn checked_add(&self, b: &num_bigint::BigInt) -> Option<num_bigint::BigInt> {
+        return Some(self + b);
+    }
+
+    fn checked_sub(&self, b: &num_bigint::BigInt) -> Option<num_bigint::BigInt> {
+        return Some(self - b);
+    }
+
+    fn checked_mul(&self, b: &num_bigint::BigInt) -> Option<num_bigint::BigInt> {
+        return Some(self * b);
+    }
+
+    fn checked_div(&self, b: &num_bigint::BigInt) -> Option<num_bigint::BigInt> {
+        if *b == num_bigint::BigInt::from(0) {
+            return None;
+        }
+        return Some(self / b);
+    }
+
+    fn checked_rem(&self, b: &num_bigint::BigInt) -> Option<num_bigint::BigInt> {
+        if *b == num_bigint::BigInt::from(0) {
+            return None;
+        }
+        return Some(self % b);
+    }
+
+    // The power has at least `(bits - 1) * exponent` bits. Way beyond the target the remaining
+    // operands can't bring it back anyway, while computing it could take forever.
+    fn checked_pow(
+        &self,
+        exponent: &num_bigint::BigInt,
+        target: &num_bigint::BigInt,
+    ) -> Option<num_bigint::BigInt> {
+        let exponent = u32::try_from(exponent).ok()?;
+        let min_bits = self
+            .bits()
+            .saturating_sub(1)
+            .saturating_mul(exponent as u64);
+
+        if min_bits > target.bits() + POWER_MARGIN_BITS {
+            return None;
+        }
+        return Some(self.pow(exponent));
+    }
+}
+
+// the power of ten `a` gets shifted by when concatenating `b` to it, e.g. 100 for b = 42
+pub fn concatenation_factor<T: Number>(b: &T) -> Option<T> {
+    let ten = T::from_i64(10);
+    let mut factor = ten.clone();
+
+    while factor <= *b {
+        factor = factor.checked_mul(&ten)?;
+    }
+    return Some(factor);
+}
+
+// `a` followed by the digits of `b`, like the numbers were written next to each other
+pub fn concatenate<T: Number>(a: &T, b: &T) -> Option<T> {
+    if *b < T::from_i64(0) {
+        return None;
+    }
+
+    let shifted = a.checked_mul(&concatenation_factor(b)?)?;
+
+    if *a < T::from_i64(0) {
+        return shifted.checked_sub(b);
+    }
+    return shifted.checked_add(b);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{concatenate, concatenation_factor, Number};
+
+    #[test]
+    fn test_concatenate() {
+        assert_eq!(concatenate(&15, &6), Some(156));
+        assert_eq!(concatenate(&15, &10), Some(1510));
+        assert_eq!(concatenate(&0, &7), Some(7));
+        assert_eq!(concatenate(&-12, &3), Some(-123));
+        assert_eq!(concatenate(&12, &-3), None);
+
+        assert_eq!(concatenation_factor(&999), Some(1000));
+        assert_eq!(concatenation_factor(&i64::MAX), None);
+        assert_eq!(concatenate(&i64::MAX, &1), None);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        assert_eq!(Number::checked_add(&i64::MAX, &1), None);
+        assert_eq!(Number::checked_mul(&i64::MAX, &2), None);
+        assert_eq!(Number::checked_div(&7, &0), None);
+        assert_eq!(Number::checked_pow(&2, &62, &0), Some(2_i64.pow(62)));
+        assert_eq!(Number::checked_pow(&2, &63, &0), None);
+        assert_eq!(Number::checked_pow(&2, &-1, &0), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_numbers() {
+        use num_bigint::BigInt;
+
+        let big = BigInt::from(i64::MAX);
+        assert_eq!(
+            Number::checked_mul(&big, &BigInt::from(10)),
+            Some("92233720368547758070".parse::<BigInt>().unwrap())
+        );
+        assert_eq!(
+            concatenate(&big, &BigInt::from(1)),
+            Some("92233720368547758071".parse::<BigInt>().unwrap())
+        );
+
+        // 2^100 is within the margin of a 64 bit target, 2^1000000 is way beyond it
+        let two = BigInt::from(2);
+        assert_eq!(
+            Number::checked_pow(&two, &BigInt::from(100), &big),
+            Some(two.pow(100))
+        );
+        assert_eq!(
+            Number::checked_pow(&two, &BigInt::from(1_000_000), &big),
+            None
+        );
+        // powers of 1 never grow
+        assert_eq!(
+            Number::checked_pow(&BigInt::from(1), &BigInt::from(u32::MAX), &big),
+            Some(BigInt::from(1))
+        );
+    }
+}
@@ -1,6 +1,12 @@
-use super::Equation;
+use super::{Equation, Number};
 
-pub fn parse(equations: &str) -> Vec<Equation> {
+fn parse_number<T: Number>(number: &str) -> Result<T, String> {
+    return number
+        .parse::<T>()
+        .map_err(|_| format!("'{}' is not a number or too big", number));
+}
+
+pub fn parse_as<T: Number>(equations: &str) -> Result<Vec<Equation<T>>, String> {
     return equations
         .trim()
         .split("\n")
@@ -9,7 +15,7 @@ pub fn parse(equations: &str) -> Vec<Equation> {
         .map(|line| {
             let line = line.trim().split(":").collect::<Vec<&str>>();
             let result = line.first().unwrap();
-            let result = result.parse::<i64>().unwrap();
+            let result = parse_number(result)?;
 
             let operands = line.last().unwrap();
             let operands = operands
@@ -17,20 +23,24 @@ pub fn parse(equations: &str) -> Vec<Equation> {
                 .split(" ")
                 .collect::<Vec<&str>>()
                 .iter()
-                .map(|op| op.parse::<i64>().unwrap())
-                .collect();
+                .map(|op| parse_number(op))
+                .collect::<Result<Vec<T>, String>>()?;
 
-            return Equation {
+            return Ok(Equation {
                 result: result,
                 operands: operands,
-            };
+            });
         })
         .collect();
 }
 
+pub fn parse(equations: &str) -> Vec<Equation> {
+    return parse_as(equations).unwrap();
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{parse, Equation};
+    use super::{parse, parse_as, Equation};
 
     #[test]
     fn test_parsing() {
@@ -52,4 +62,13 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_parsing_too_big_numbers() {
+        let result = parse_as::<i64>("99999999999999999999: 1 2");
+        assert_eq!(
+            result,
+            Err("'99999999999999999999' is not a number or too big".to_string())
+        );
+    }
 }
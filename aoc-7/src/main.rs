@@ -1,15 +1,113 @@
+use std::env;
 use std::fs;
 
 mod calibration;
-use calibration::{calibrate, parse};
+use calibration::{
+    calibrate, calibrate_with, parenthesise, parse, parse_operator, solve_all, Equation,
+    Evaluation, Number, Operator, PART_ONE_OPERATORS, PART_TWO_OPERATORS,
+};
+
+fn report<T: Number>(equations: &Vec<Equation<T>>, args: &Vec<String>) {
+    let result = calibrate(&equations, &PART_ONE_OPERATORS.to_vec()).expect("result overflows");
+    println!("result with adding and multiplying: {}", result);
+
+    let result = calibrate(&equations, &PART_TWO_OPERATORS.to_vec()).expect("result overflows");
+    println!("result with concatenating as well: {}", result);
+
+    // `cargo run -- --operators "+ - / ^"` calibrates with any other set of operators
+    if let Some(index) = args.iter().position(|arg| arg == "--operators") {
+        let usage = "usage: --operators \"+ * ||\" (any of + - * / ^ ||)";
+
+        let Some(symbols) = args.get(index + 1) else {
+            eprintln!("{}", usage);
+            return;
+        };
+        let operators: Vec<Operator> = match symbols
+            .split_whitespace()
+            .map(parse_operator)
+            .collect::<Result<Vec<Operator>, String>>()
+        {
+            Ok(operators) => operators,
+            Err(error) => {
+                eprintln!("{}\n{}", error, usage);
+                return;
+            }
+        };
+
+        let result = calibrate(&equations, &operators).expect("result overflows");
+        println!("result with {}: {}", symbols, result);
+
+        // `cargo run -- --operators "+ *" --show` lists how every equation got solved
+        if args.iter().any(|arg| arg == "--show") {
+            for solution in solve_all(&equations, &operators) {
+                println!("{} ({} possibilities)", solution, solution.count);
+            }
+        }
+    }
+}
+
+// `cargo run -r -- --evaluation precedence` (or `parenthesised`, `left-to-right`) evaluates
+// the equations with the usual operator precedence or any parentheses instead of strictly
+// left to right
+fn report_evaluation<T: Number>(equations: &Vec<Equation<T>>, args: &Vec<String>) {
+    let Some(index) = args.iter().position(|arg| arg == "--evaluation") else {
+        return;
+    };
+
+    let evaluation = match args.get(index + 1).map(|value| value.as_str()) {
+        Some("left-to-right") => Evaluation::LeftToRight,
+        Some("precedence") => Evaluation::Precedence,
+        Some("parenthesised") => Evaluation::Parenthesised,
+        _ => {
+            eprintln!("usage: --evaluation left-to-right|precedence|parenthesised");
+            return;
+        }
+    };
+
+    let result = calibrate_with(&equations, &PART_ONE_OPERATORS.to_vec(), evaluation)
+        .expect("result overflows");
+    println!(
+        "result with adding and multiplying ({:?}): {}",
+        evaluation, result
+    );
+
+    // concatenating within parentheses gives way too many intermediate values for the real input
+    if evaluation == Evaluation::Parenthesised {
+        if args.iter().any(|arg| arg == "--show") {
+            for equation in equations {
+                if let Ok((expression, count)) =
+                    parenthesise(equation, &PART_ONE_OPERATORS.to_vec())
+                {
+                    println!("{} ({} possibilities)", expression, count);
+                }
+            }
+        }
+        return;
+    }
+
+    let result = calibrate_with(&equations, &PART_TWO_OPERATORS.to_vec(), evaluation)
+        .expect("result overflows");
+    println!(
+        "result with concatenating as well ({:?}): {}",
+        evaluation, result
+    );
+}
 
 fn main() {
     let equations =
         fs::read_to_string("./data/equations.txt").expect("Should have been able to read the file");
+    let args: Vec<String> = env::args().collect();
 
-    let equations = parse(&equations);
-
-    let result = calibrate(equations);
+    // `cargo run --features bigint -- --big` calibrates equations exceeding 64 bit numbers
+    #[cfg(feature = "bigint")]
+    if args.iter().any(|arg| arg == "--big") {
+        let equations = calibration::parse_as::<num_bigint::BigInt>(&equations).unwrap();
+        report(&equations, &args);
+        report_evaluation(&equations, &args);
+        return;
+    }
 
-    println!("result: {}", result)
+    let equations = parse(&equations);
+    report(&equations, &args);
+    report_evaluation(&equations, &args);
 }
@@ -1,12 +1,18 @@
+use std::collections::{HashMap, HashSet};
+
+mod render;
 mod util;
 
-pub use util::parse;
-use util::{draw_antinodes, unique_chars};
+pub use render::{render_antinodes, Overlay};
+use util::unique_chars;
+pub use util::{parse, parse_antenna_list};
 
 pub type AntennaMap = Vec<Vec<char>>;
 pub type Location = (i64, i64);
 pub type AntennaLocation = (i64, i64, char);
 pub type Vector = (i64, i64);
+// the width and height of the area antinodes can be in
+pub type Bounds = (i64, i64);
 
 fn find_frequencies(map: &AntennaMap) -> Vec<char> {
     return unique_chars(&map);
@@ -25,8 +31,27 @@ fn locate_antennas(map: &AntennaMap, frequency: &char) -> Vec<AntennaLocation> {
     return locations;
 }
 
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Step {
+    // from antenna to antenna, skipping the locations in between
+    Distance,
+    // the distance divided by the greatest common divisor of its coordinates, which reaches every
+    // location on the line through the antennas. Only the harmonics are affected by this.
+    Reduced,
+}
+
+fn greatest_common_divisor(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    return a;
+}
+
 fn antenna_connections(
     locations: &Vec<AntennaLocation>,
+    step: Step,
 ) -> Vec<(Vector, AntennaLocation, AntennaLocation)> {
     let mut connections: Vec<(Vector, AntennaLocation, AntennaLocation)> = vec![];
 
@@ -35,13 +60,19 @@ fn antenna_connections(
             let (x1, y1, c1) = locations[i].clone();
             let (x2, y2, c2) = locations[j].clone();
 
-            let distance_x = x2 - x1;
-            let distance_y = y2 - y1;
+            let mut distance_x = x2 - x1;
+            let mut distance_y = y2 - y1;
 
             if distance_x == 0 && distance_y == 0 {
                 continue;
             }
 
+            if step == Step::Reduced {
+                let divisor = greatest_common_divisor(distance_x, distance_y);
+                distance_x /= divisor;
+                distance_y /= divisor;
+            }
+
             connections.push(((distance_x, distance_y), (x1, y1, c1), (x2, y2, c2)));
         }
     }
@@ -49,10 +80,11 @@ fn antenna_connections(
     return connections;
 }
 
-fn resonating(map: &AntennaMap, (x, y): Location, (dist_x, dist_y): Vector) -> Option<Location> {
-    let width = map[0].len() as i64;
-    let height = map.len() as i64;
-
+fn resonating(
+    (width, height): Bounds,
+    (x, y): Location,
+    (dist_x, dist_y): Vector,
+) -> Option<Location> {
     let (x, y) = (x + dist_x, y + dist_y);
 
     if x >= 0 && x < width && y >= 0 && y < height {
@@ -62,42 +94,137 @@ fn resonating(map: &AntennaMap, (x, y): Location, (dist_x, dist_y): Vector) -> O
     return None;
 }
 
-fn collect(antinodes: &mut Vec<Location>, location: &Location) {
-    if !antinodes.contains(location) {
-        antinodes.push(location.clone());
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AntinodeRule {
+    // one antinode on each side of a pair, as far away as the antennas are from each other
+    PairDistance,
+    // the antennas themselves and every multiple of their distance up to the edge of the map
+    Harmonics,
+    // the antennas themselves and at most this many multiples of their distance on each side
+    LimitedHarmonics(usize),
+}
+
+impl AntinodeRule {
+    fn includes_antennas(&self) -> bool {
+        return *self != AntinodeRule::PairDistance;
+    }
+
+    fn max_harmonics(&self) -> usize {
+        return match self {
+            AntinodeRule::PairDistance => 1,
+            AntinodeRule::Harmonics => usize::MAX,
+            AntinodeRule::LimitedHarmonics(count) => *count,
+        };
+    }
+}
+
+// Walks from the start in steps of the given distance, collecting up to `max_harmonics`
+// antinodes. Whatever is on the map there doesn't matter, antinodes can overlap antennas.
+fn collect_harmonics(
+    bounds: Bounds,
+    antinodes: &mut HashSet<Location>,
+    start: Location,
+    distance: Vector,
+    max_harmonics: usize,
+) {
+    let mut next_start: Location = start;
+
+    for _ in 0..max_harmonics {
+        let Some(next) = resonating(bounds, next_start, distance) else {
+            break;
+        };
+        next_start = next;
+        antinodes.insert(next);
     }
 }
 
-pub fn find_antinodes(map: AntennaMap) -> Result<u64, String> {
-    let frequencies = find_frequencies(&map);
-    let mut antinodes: Vec<Location> = vec![];
+// The antinodes of every frequency on its own, an antinode can belong to multiple frequencies.
+// The antennas don't have to be on a map, only within the bounds.
+pub fn antinodes_of_antennas(
+    antennas: &Vec<AntennaLocation>,
+    bounds: Bounds,
+    rule: AntinodeRule,
+    step: Step,
+) -> HashMap<char, HashSet<Location>> {
+    let mut antinodes_per_frequency: HashMap<char, HashSet<Location>> = HashMap::new();
+    // the pairs are only as far apart as the distance when stepping by it
+    let step = if rule.includes_antennas() {
+        step
+    } else {
+        Step::Distance
+    };
+
+    for (_, _, frequency) in antennas {
+        if antinodes_per_frequency.contains_key(frequency) {
+            continue;
+        }
 
-    for frequency in frequencies {
-        let antennas = locate_antennas(&map, &frequency);
-        let connections = antenna_connections(&antennas);
+        let antennas: Vec<AntennaLocation> = antennas
+            .iter()
+            .filter(|(_, _, antenna_frequency)| antenna_frequency == frequency)
+            .cloned()
+            .collect();
+        let connections = antenna_connections(&antennas, step);
+        let mut antinodes: HashSet<Location> = HashSet::new();
 
         for ((dist_x, dist_y), (x1, y1, _), (x2, y2, _)) in connections {
-            let mut next_start: Location = (x1, y1);
-            collect(&mut antinodes, &(x1, y1));
-            collect(&mut antinodes, &(x2, y2));
-
-            while let Some(next) = resonating(&map, next_start, (-1 * dist_x, -1 * dist_y)) {
-                next_start = next;
-                if map[next.1 as usize][next.0 as usize] == '.' {
-                    collect(&mut antinodes, &next);
+            if rule.includes_antennas() {
+                antinodes.insert((x1, y1));
+                antinodes.insert((x2, y2));
+
+                // with a reduced step there are more antinodes between the antennas
+                let mut between: Location = (x1 + dist_x, y1 + dist_y);
+                while between != (x2, y2) {
+                    antinodes.insert(between);
+                    between = (between.0 + dist_x, between.1 + dist_y);
                 }
             }
 
-            let mut next_start: Location = (x2, y2);
-            while let Some(next) = resonating(&map, next_start, (dist_x, dist_y)) {
-                next_start = next;
-                if map[next.1 as usize][next.0 as usize] == '.' {
-                    collect(&mut antinodes, &next);
-                }
-            }
+            collect_harmonics(
+                bounds,
+                &mut antinodes,
+                (x1, y1),
+                (-1 * dist_x, -1 * dist_y),
+                rule.max_harmonics(),
+            );
+            collect_harmonics(
+                bounds,
+                &mut antinodes,
+                (x2, y2),
+                (dist_x, dist_y),
+                rule.max_harmonics(),
+            );
         }
+
+        antinodes_per_frequency.insert(*frequency, antinodes);
     }
-    draw_antinodes(&map, &antinodes);
+    return antinodes_per_frequency;
+}
+
+pub fn antinodes_per_frequency(
+    map: &AntennaMap,
+    rule: AntinodeRule,
+    step: Step,
+) -> HashMap<char, HashSet<Location>> {
+    let antennas: Vec<AntennaLocation> = find_frequencies(&map)
+        .iter()
+        .flat_map(|frequency| locate_antennas(&map, frequency))
+        .collect();
+    let bounds: Bounds = (map[0].len() as i64, map.len() as i64);
+
+    return antinodes_of_antennas(&antennas, bounds, rule, step);
+}
+
+// all locations with an antinode of any frequency
+pub fn distinct_antinodes(
+    antinodes_per_frequency: HashMap<char, HashSet<Location>>,
+) -> HashSet<Location> {
+    return antinodes_per_frequency.into_values().flatten().collect();
+}
+
+pub fn find_antinodes(map: AntennaMap, rule: AntinodeRule) -> Result<u64, String> {
+    let antinodes = distinct_antinodes(antinodes_per_frequency(&map, rule, Step::Distance));
+
     return Ok(antinodes.len() as u64);
 }
 
@@ -109,20 +236,25 @@ pub fn find_antinodes(map: AntennaMap) -> Result<u64, String> {
 
 #[cfg(test)]
 mod tests {
-    use super::{antenna_connections, find_antinodes, find_frequencies, locate_antennas, parse};
+    use super::{
+        antenna_connections, antinodes_of_antennas, antinodes_per_frequency, distinct_antinodes,
+        find_antinodes, find_frequencies, locate_antennas, parse, parse_antenna_list, AntinodeRule,
+        Step,
+    };
+    use std::collections::HashSet;
 
     #[test]
     fn test_antenna_connections() {
         let locations = vec![(7, 0, 'A'), (4, 1, 'A')];
-        let result = antenna_connections(&locations);
+        let result = antenna_connections(&locations, Step::Distance);
         assert_eq!(result, vec![((-3, 1), (7, 0, 'A'), (4, 1, 'A'))]);
 
         let locations = vec![(5, 0, 'A'), (3, 2, 'A')];
-        let result = antenna_connections(&locations);
+        let result = antenna_connections(&locations, Step::Distance);
         assert_eq!(result, vec![((-2, 2), (5, 0, 'A'), (3, 2, 'A'))]);
 
         let locations = vec![(5, 0, 'A'), (3, 2, 'A'), (4, 4, 'A')];
-        let result = antenna_connections(&locations);
+        let result = antenna_connections(&locations, Step::Distance);
         assert_eq!(
             result,
             vec![
@@ -186,8 +318,217 @@ mod tests {
              ............",
         );
 
-        let result = find_antinodes(antenna_map);
+        let result = find_antinodes(antenna_map, AntinodeRule::Harmonics);
 
         assert_eq!(result, Ok(34));
     }
+
+    #[test]
+    fn test_pair_distance() {
+        let antenna_map = parse(
+            "..........
+             ..........
+             ..........
+             ....a.....
+             ........a.
+             .....a....
+             ..........
+             ..........
+             ..........
+             ..........",
+        );
+
+        let result = find_antinodes(antenna_map, AntinodeRule::PairDistance);
+
+        // two of the six antinodes are outside of the map
+        assert_eq!(result, Ok(4));
+    }
+
+    #[test]
+    fn test_limited_harmonics() {
+        let antenna_map = parse(
+            "T.........
+             ...T......
+             .T........
+             ..........
+             ..........
+             ..........
+             ..........
+             ..........
+             ..........
+             ..........",
+        );
+
+        let result = find_antinodes(antenna_map.clone(), AntinodeRule::Harmonics);
+        assert_eq!(result, Ok(9));
+
+        // the antennas plus the first harmonic of each pair within the map
+        let result = find_antinodes(antenna_map.clone(), AntinodeRule::LimitedHarmonics(1));
+        assert_eq!(result, Ok(6));
+
+        let result = find_antinodes(antenna_map, AntinodeRule::LimitedHarmonics(0));
+        assert_eq!(result, Ok(3));
+    }
+
+    #[test]
+    fn test_antinodes_on_antennas() {
+        let antenna_map = parse(
+            "............
+             ........0...
+             .....0......
+             .......0....
+             ....0.......
+             ......A.....
+             ............
+             ............
+             ........A...
+             .........A..
+             ............
+             ............",
+        );
+
+        // one of the antinodes of `0` is where the topmost `A` is
+        let result = find_antinodes(antenna_map.clone(), AntinodeRule::PairDistance);
+        assert_eq!(result, Ok(14));
+
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::PairDistance, Step::Distance);
+        assert!(antinodes[&'0'].contains(&(6, 5)));
+        assert_eq!(antinodes[&'0'].len() + antinodes[&'A'].len(), 15);
+    }
+
+    #[test]
+    fn test_overlapping_frequencies() {
+        let antenna_map = parse(
+            "......
+             .a.b..
+             ..a.b.
+             ......
+             ......",
+        );
+
+        // both pairs have the same distance, so `b` has an antinode on the other `a`
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::PairDistance, Step::Distance);
+        assert_eq!(antinodes[&'a'], HashSet::from([(0, 0), (3, 3)]));
+        assert_eq!(antinodes[&'b'], HashSet::from([(2, 0), (5, 3)]));
+
+        let antenna_map = parse(
+            "......
+             .a....
+             ..ab..
+             ...b..
+             ......",
+        );
+
+        // an antinode of `a` is on the lower `b`, where the harmonics of both cross as well
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::PairDistance, Step::Distance);
+        assert_eq!(antinodes[&'a'], HashSet::from([(0, 0), (3, 3)]));
+        assert_eq!(antinodes[&'b'], HashSet::from([(3, 1), (3, 4)]));
+
+        // five on the diagonal and five in the column, sharing one
+        let result = find_antinodes(antenna_map, AntinodeRule::Harmonics);
+        assert_eq!(result, Ok(9));
+    }
+
+    #[test]
+    fn test_reduced_step() {
+        let locations = vec![(0, 0, 'a'), (4, 2, 'a')];
+        let result = antenna_connections(&locations, Step::Reduced);
+        assert_eq!(result, vec![((2, 1), (0, 0, 'a'), (4, 2, 'a'))]);
+
+        let locations = vec![(6, 0, 'a'), (0, 9, 'a')];
+        let result = antenna_connections(&locations, Step::Reduced);
+        assert_eq!(result, vec![((-2, 3), (6, 0, 'a'), (0, 9, 'a'))]);
+
+        let antenna_map = parse(
+            "a.........
+             ..........
+             ....a.....
+             ..........
+             ..........",
+        );
+
+        // stepping by (4, 2) skips (2, 1) and (6, 3)
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::Harmonics, Step::Distance);
+        assert_eq!(antinodes[&'a'], HashSet::from([(0, 0), (4, 2), (8, 4)]));
+
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::Harmonics, Step::Reduced);
+        assert_eq!(
+            antinodes[&'a'],
+            HashSet::from([(0, 0), (2, 1), (4, 2), (6, 3), (8, 4)])
+        );
+
+        // the pairs still count at their full distance
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::PairDistance, Step::Reduced);
+        assert_eq!(antinodes[&'a'], HashSet::from([(8, 4)]));
+    }
+
+    #[test]
+    fn test_antenna_list() {
+        let (bounds, antennas) = parse_antenna_list(
+            "12 x 12
+             8, 1, 0
+             5, 2, 0
+             7, 3, 0
+             4, 4, 0
+             6, 5, A
+             8, 8, A
+             9, 9, A",
+        )
+        .unwrap();
+        assert_eq!(bounds, (12, 12));
+        assert_eq!(antennas[4], (6, 5, 'A'));
+
+        // the same as the map of the example
+        let antinodes = antinodes_of_antennas(
+            &antennas,
+            bounds,
+            AntinodeRule::PairDistance,
+            Step::Distance,
+        );
+        assert_eq!(distinct_antinodes(antinodes).len(), 14);
+        let antinodes =
+            antinodes_of_antennas(&antennas, bounds, AntinodeRule::Harmonics, Step::Distance);
+        assert_eq!(distinct_antinodes(antinodes).len(), 34);
+
+        // unlike on a map, antennas of different frequencies can be at the same location
+        let (bounds, antennas) = parse_antenna_list(
+            "5 x 1
+             1, 0, a
+             2, 0, a
+             1, 0, b
+             3, 0, b",
+        )
+        .unwrap();
+        let antinodes = antinodes_of_antennas(
+            &antennas,
+            bounds,
+            AntinodeRule::PairDistance,
+            Step::Distance,
+        );
+        assert_eq!(antinodes[&'a'], HashSet::from([(0, 0), (3, 0)]));
+        assert_eq!(antinodes[&'b'], HashSet::new());
+
+        assert_eq!(
+            parse_antenna_list("5 x 1\n 5, 0, a"),
+            Err("'5, 0, a' is out of bounds".to_string())
+        );
+        assert_eq!(
+            parse_antenna_list("5 x 1\n 1, 0"),
+            Err("'1, 0' is not an antenna".to_string())
+        );
+        assert_eq!(
+            parse_antenna_list("5 x 1\n 1, 0, ab"),
+            Err("'1, 0, ab' has no single character frequency".to_string())
+        );
+        assert_eq!(
+            parse_antenna_list("five x 1"),
+            Err("'five' is not a number".to_string())
+        );
+    }
 }
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use super::{AntennaMap, Location};
+
+// colours of the frequencies in alphabetical order, repeating for more than six of them
+const COLORS: [u8; 6] = [31, 32, 33, 34, 35, 36];
+
+// What gets drawn on top of the antenna map. Antinodes are drawn as `#`, unless there is an
+// antenna at their location.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Overlay {
+    // only the antennas and antinodes of these frequencies, all of them if `None`
+    pub frequencies: Option<Vec<char>>,
+    // colours every antinode like its frequency using ANSI escape codes
+    pub color: bool,
+}
+
+impl Overlay {
+    fn shows(&self, frequency: &char) -> bool {
+        return match &self.frequencies {
+            Some(frequencies) => frequencies.contains(frequency),
+            None => true,
+        };
+    }
+}
+
+pub fn render_antinodes(
+    map: &AntennaMap,
+    antinodes: &HashMap<char, HashSet<Location>>,
+    overlay: &Overlay,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut frequencies: Vec<&char> = antinodes
+        .keys()
+        .filter(|frequency| overlay.shows(frequency))
+        .collect();
+    frequencies.sort();
+
+    for (y, line) in map.iter().enumerate() {
+        for (x, field) in line.iter().enumerate() {
+            let field = if overlay.shows(field) { *field } else { '.' };
+            // the first frequency in alphabetical order wins where antinodes overlap
+            let antinode = frequencies
+                .iter()
+                .position(|frequency| antinodes[frequency].contains(&(x as i64, y as i64)));
+
+            match antinode {
+                None => write!(out, "{}", field)?,
+                Some(_) if !overlay.color && field == '.' => write!(out, "#")?,
+                Some(_) if !overlay.color => write!(out, "{}", field)?,
+                Some(index) => {
+                    let field = if field == '.' { '#' } else { field };
+                    write!(
+                        out,
+                        "\x1b[{}m{}\x1b[0m",
+                        COLORS[index % COLORS.len()],
+                        field
+                    )?
+                }
+            }
+        }
+        writeln!(out)?;
+    }
+
+    return Ok(());
+}
+
+// ###############################################################################################################
+// ###############################################################################################################
+// # TESTS                                                                                                       #
+// ###############################################################################################################
+// ###############################################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::{render_antinodes, Overlay};
+    use crate::frequencies::{antinodes_per_frequency, parse, AntinodeRule, Step};
+
+    const EXAMPLE_MAP: &str = "............
+             ........0...
+             .....0......
+             .......0....
+             ....0.......
+             ......A.....
+             ............
+             ............
+             ........A...
+             .........A..
+             ............
+             ............";
+
+    fn render(overlay: &Overlay) -> String {
+        let map = parse(EXAMPLE_MAP);
+        let antinodes = antinodes_per_frequency(&map, AntinodeRule::PairDistance, Step::Distance);
+        let mut out: Vec<u8> = vec![];
+
+        render_antinodes(&map, &antinodes, overlay, &mut out).unwrap();
+        return String::from_utf8(out).unwrap();
+    }
+
+    #[test]
+    fn test_render_antinodes() {
+        assert_eq!(
+            render(&Overlay::default()),
+            "......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#.
+"
+        );
+    }
+
+    #[test]
+    fn test_filter_frequencies() {
+        let overlay = Overlay {
+            frequencies: Some(vec!['A']),
+            ..Overlay::default()
+        };
+
+        assert_eq!(
+            render(&overlay),
+            "............
+...#........
+....#.......
+............
+............
+......A.....
+............
+.......#....
+........A...
+.........A..
+..........#.
+..........#.
+"
+        );
+    }
+
+    #[test]
+    fn test_colors() {
+        let overlay = Overlay {
+            frequencies: Some(vec!['A']),
+            color: true,
+        };
+
+        let rendered = render(&overlay);
+        assert!(rendered.starts_with("............\n...\x1b[31m#\x1b[0m........\n"));
+        assert!(rendered.contains("......A.....\n"));
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use super::{AntennaMap, Location};
+use super::{AntennaLocation, AntennaMap, Bounds};
 
 pub fn parse(antenna_map: &str) -> AntennaMap {
     return antenna_map
@@ -12,6 +12,50 @@ pub fn parse(antenna_map: &str) -> AntennaMap {
         .collect::<AntennaMap>();
 }
 
+// A list of antennas instead of a map, starting with the bounds:
+//
+// 12 x 12
+// 8, 1, 0
+// 5, 2, 0
+pub fn parse_antenna_list(list: &str) -> Result<(Bounds, Vec<AntennaLocation>), String> {
+    let mut lines = list.trim().lines().map(|line| line.trim());
+
+    let bounds = lines.next().ok_or("the bounds are missing")?;
+    let (width, height) = bounds
+        .split_once("x")
+        .ok_or(format!("'{}' are no bounds", bounds))?;
+    let bounds: Bounds = (parse_number(width)?, parse_number(height)?);
+
+    let mut antennas: Vec<AntennaLocation> = vec![];
+
+    for line in lines {
+        let parts: Vec<&str> = line.split(",").map(|part| part.trim()).collect();
+        let [x, y, frequency] = parts[..] else {
+            return Err(format!("'{}' is not an antenna", line));
+        };
+
+        let mut frequency = frequency.chars();
+        let (Some(frequency), None) = (frequency.next(), frequency.next()) else {
+            return Err(format!("'{}' has no single character frequency", line));
+        };
+        let (x, y) = (parse_number(x)?, parse_number(y)?);
+
+        if x < 0 || x >= bounds.0 || y < 0 || y >= bounds.1 {
+            return Err(format!("'{}' is out of bounds", line));
+        }
+        antennas.push((x, y, frequency));
+    }
+
+    return Ok((bounds, antennas));
+}
+
+fn parse_number(number: &str) -> Result<i64, String> {
+    return number
+        .trim()
+        .parse::<i64>()
+        .map_err(|_| format!("'{}' is not a number", number.trim()));
+}
+
 pub fn unique_chars(map: &AntennaMap) -> Vec<char> {
     let map = map.clone();
     let mut seen = HashSet::new();
@@ -23,21 +67,3 @@ pub fn unique_chars(map: &AntennaMap) -> Vec<char> {
         .filter(|x| *x != '.' && seen.insert(x.clone()))
         .collect::<Vec<char>>();
 }
-
-pub fn draw_antinodes(map: &AntennaMap, antinodes: &Vec<Location>) {
-    let mut map = map.clone();
-
-    for (x, y) in antinodes {
-        map[*y as usize][*x as usize] = '#';
-    }
-
-    let map = map
-        .iter()
-        .map(|line| {
-            return String::from_iter(line);
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    println!("{}", map);
-}
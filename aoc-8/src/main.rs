@@ -1,15 +1,104 @@
+use std::env;
 use std::fs;
+use std::io;
 
 mod frequencies;
-use frequencies::{find_antinodes, parse};
+use frequencies::{
+    antinodes_of_antennas, antinodes_per_frequency, distinct_antinodes, find_antinodes, parse,
+    parse_antenna_list, render_antinodes, AntinodeRule, Overlay, Step,
+};
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // `cargo run -- --reduced` steps to every location on the line through the antennas
+    let step = match args.iter().any(|arg| arg == "--reduced") {
+        true => Step::Reduced,
+        false => Step::Distance,
+    };
+
+    // `cargo run -- --antennas antennas.txt` reads a list of antennas instead of the map
+    if let Some(index) = args.iter().position(|arg| arg == "--antennas") {
+        let Some(path) = args.get(index + 1) else {
+            eprintln!("usage: --antennas <file with a list of antennas>");
+            return;
+        };
+        let list = fs::read_to_string(path).expect("Should have been able to read the file");
+        let (bounds, antennas) = parse_antenna_list(&list).unwrap();
+
+        for rule in [AntinodeRule::PairDistance, AntinodeRule::Harmonics] {
+            let antinodes = antinodes_of_antennas(&antennas, bounds, rule, step);
+            println!(
+                "found {} distinct antinodes ({:?})",
+                distinct_antinodes(antinodes).len(),
+                rule
+            );
+        }
+        return;
+    }
+
     let antenna_map = fs::read_to_string("./data/antenna_map.txt")
         .expect("Should have been able to read the file");
 
     let antenna_map = parse(&antenna_map);
 
-    let anti_node_count = find_antinodes(antenna_map).unwrap();
-
+    let anti_node_count = find_antinodes(antenna_map.clone(), AntinodeRule::PairDistance).unwrap();
     println!("found {} distinct antinodes", anti_node_count);
+
+    let anti_node_count = find_antinodes(antenna_map.clone(), AntinodeRule::Harmonics).unwrap();
+    println!(
+        "found {} distinct antinodes with resonant harmonics",
+        anti_node_count
+    );
+
+    if step == Step::Reduced {
+        let antinodes = antinodes_per_frequency(&antenna_map, AntinodeRule::Harmonics, step);
+        println!(
+            "found {} distinct antinodes with resonant harmonics in reduced steps",
+            distinct_antinodes(antinodes).len()
+        );
+    }
+
+    // `cargo run -- --harmonics 3` only resonates up to the third multiple of the distance
+    if let Some(index) = args.iter().position(|arg| arg == "--harmonics") {
+        let Some(count) = args
+            .get(index + 1)
+            .and_then(|value| value.parse::<usize>().ok())
+        else {
+            eprintln!("usage: --harmonics <number of harmonics, e.g. 3>");
+            return;
+        };
+        let antinodes =
+            antinodes_per_frequency(&antenna_map, AntinodeRule::LimitedHarmonics(count), step);
+        println!(
+            "found {} distinct antinodes with up to {} harmonics",
+            distinct_antinodes(antinodes).len(),
+            count
+        );
+    }
+
+    // `cargo run -- --draw` shows the antinodes of both parts on the map,
+    // `--only 0A` only the ones of some frequencies and `--color` colours them
+    if args.iter().any(|arg| arg == "--draw") {
+        let frequencies = match args.iter().position(|arg| arg == "--only") {
+            Some(index) => match args.get(index + 1) {
+                Some(frequencies) => Some(frequencies.chars().collect()),
+                None => {
+                    eprintln!("usage: --only <frequencies, e.g. 0A>");
+                    return;
+                }
+            },
+            None => None,
+        };
+        let overlay = Overlay {
+            frequencies,
+            color: args.iter().any(|arg| arg == "--color"),
+        };
+
+        for rule in [AntinodeRule::PairDistance, AntinodeRule::Harmonics] {
+            let antinodes = antinodes_per_frequency(&antenna_map, rule, step);
+            render_antinodes(&antenna_map, &antinodes, &overlay, &mut io::stdout()).unwrap();
+            println!();
+        }
+    }
 }
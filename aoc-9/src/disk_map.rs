@@ -0,0 +1,182 @@
+// a run of blocks of the same file, or of free space without an id
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Block {
+    pub id: Option<u64>,
+    pub length: u64,
+}
+
+pub fn new_free_block(length: u64) -> Block {
+    Block { id: None, length }
+}
+
+pub fn new_file(id: u64, length: u64) -> Block {
+    Block {
+        id: Some(id),
+        length,
+    }
+}
+
+impl Block {
+    pub fn is_file(&self) -> bool {
+        return self.id.is_some();
+    }
+}
+
+// The disk as runs of blocks from the start to the end, shared by all compaction strategies.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiskMap {
+    pub blocks: Vec<Block>,
+}
+
+// How the files get moved to the front of the disk.
+pub trait Compactor {
+    fn compact(&self, disk_map: &DiskMap) -> DiskMap;
+}
+
+impl DiskMap {
+    // The dense format alternates between the lengths of files and free space, so the blocks
+    // can be taken from it directly without expanding them into single ones.
+    pub fn parse(disk_map: &str) -> DiskMap {
+        let mut id: u64 = 0;
+
+        let mut blocks: Vec<Block> = vec![];
+
+        for (index, number) in disk_map.trim().chars().enumerate() {
+            let length = number.to_string().parse::<u64>().unwrap();
+
+            if index % 2 == 0 {
+                blocks.push(new_file(id, length));
+                id += 1;
+            } else {
+                blocks.push(new_free_block(length));
+            }
+        }
+
+        return DiskMap { blocks };
+    }
+
+    // The files placed at the given start positions, free space everywhere else. The files
+    // must not overlap.
+    pub fn from_files(mut files: Vec<(u64, Block)>, disk_length: u64) -> DiskMap {
+        files.sort_by_key(|(start, _)| *start);
+
+        let mut blocks: Vec<Block> = vec![];
+        let mut position = 0;
+
+        for (start, file) in files {
+            if start > position {
+                blocks.push(new_free_block(start - position));
+            }
+            blocks.push(file);
+            // an empty file might share its start with the file in front of it
+            position = position.max(start + file.length);
+        }
+
+        if disk_length > position {
+            blocks.push(new_free_block(disk_length - position));
+        }
+
+        return DiskMap { blocks };
+    }
+
+    pub fn length(&self) -> u64 {
+        return self.blocks.iter().map(|block| block.length).sum();
+    }
+
+    // the sum of position times id for all blocks, a run adds up its positions at once
+    pub fn checksum(&self) -> u64 {
+        let mut sum = 0;
+        let mut position = 0;
+
+        for Block { id, length } in &self.blocks {
+            if let Some(id) = id {
+                let positions = length * position + length * length.saturating_sub(1) / 2;
+                sum += positions * id;
+            }
+            position += length;
+        }
+
+        return sum;
+    }
+
+    // For ids > 9 the rendered ids take more place, so this only shows the layout of small disks
+    // the way the puzzle does.
+    pub fn render(&self) -> String {
+        return self
+            .blocks
+            .iter()
+            .map(|block| {
+                if let Some(id) = block.id {
+                    return id.to_string().repeat(block.length as usize);
+                } else {
+                    return ".".repeat(block.length as usize);
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("");
+    }
+}
+
+// ###############################################################################################################
+// ###############################################################################################################
+// # TESTS                                                                                                       #
+// ###############################################################################################################
+// ###############################################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::{new_file, new_free_block, DiskMap};
+
+    #[test]
+    fn test_parse() {
+        let compact_format = "2333133121414131402";
+        let result = DiskMap::parse(compact_format);
+        assert_eq!(
+            result.render(),
+            "00...111...2...333.44.5555.6666.777.888899"
+        );
+        assert_eq!(result.length(), 42);
+    }
+
+    #[test]
+    fn test_from_files() {
+        let files = vec![(5, new_file(1, 2)), (0, new_file(0, 3))];
+        let result = DiskMap::from_files(files, 9);
+
+        assert_eq!(
+            result.blocks,
+            vec![
+                new_file(0, 3),
+                new_free_block(2),
+                new_file(1, 2),
+                new_free_block(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_files_with_empty_file() {
+        let files = vec![(1, new_file(0, 3)), (2, new_file(1, 0))];
+        let result = DiskMap::from_files(files, 5);
+
+        assert_eq!(result.render(), ".000.");
+        assert_eq!(result.length(), 5);
+    }
+
+    #[test]
+    fn test_checksum() {
+        // 0 * 0 + 1 * 0 + 2 * 1 + 3 * 1 + 4 * 1
+        let disk_map = DiskMap {
+            blocks: vec![new_file(0, 2), new_file(1, 3)],
+        };
+        assert_eq!(disk_map.checksum(), 9);
+
+        let disk_map = DiskMap {
+            blocks: vec![new_free_block(1), new_file(5, 1), new_file(6, 0)],
+        };
+        assert_eq!(disk_map.checksum(), 5);
+
+        // 18 * 1 + 19 * 1 + ... + 26 * 1 without any compaction
+        assert_eq!(DiskMap::parse("999").checksum(), 198);
+    }
+}
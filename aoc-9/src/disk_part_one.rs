@@ -1,63 +1,55 @@
-fn parse_disk_map(disk_map: &str) -> Vec<Option<u64>> {
-    let mut id: u64 = 0;
+use crate::disk_map::{new_free_block, Block, Compactor, DiskMap};
 
-    let mut parsed: Vec<Option<u64>> = vec![];
+// Moves single blocks of the files at the end into the free space at the front.
+pub struct BlockWise;
 
-    for (index, number) in disk_map.trim().chars().into_iter().enumerate() {
-        let size = number.to_string().parse::<usize>().unwrap();
-        let rest = index % 2;
+impl Compactor for BlockWise {
+    // Fills the free blocks from the front with the blocks of the files from the back. Both ends
+    // move towards each other, the files at the back get shorter while they are moved.
+    fn compact(&self, disk_map: &DiskMap) -> DiskMap {
+        let mut blocks = disk_map.blocks.clone();
+        let mut compacted: Vec<Block> = vec![];
 
-        let mut block = None;
+        let mut front = 0;
+        let mut back = blocks.len(); // everything from here on is already moved
 
-        if rest == 0 {
-            block = Some(id);
-            id += 1;
-        }
-
-        vec![1; size].iter().for_each(|_| parsed.push(block));
-    }
-
-    return parsed;
-}
-
-fn compact(disk_map: Vec<Option<u64>>) -> Vec<Option<u64>> {
-    let mut compacted = disk_map.clone();
+        while front < back {
+            let block = blocks[front];
+            front += 1;
 
-    for (rev_index, number_from_behind) in disk_map.iter().rev().enumerate() {
-        if let Some(number_from_behind) = number_from_behind {
-            let next_free_space = compacted.iter().enumerate().find(|(_, el)| el.is_none());
+            if block.is_file() {
+                compacted.push(block);
+                continue;
+            }
 
-            if let Some((next_dot_index, _)) = next_free_space {
-                let original_index = disk_map.len() - rev_index - 1;
+            let mut free = block.length;
 
-                if original_index < next_dot_index {
-                    break; // replaced all possibilities. Rest of dots is in the end of the string
+            while free > 0 {
+                while back > front && (!blocks[back - 1].is_file() || blocks[back - 1].length == 0)
+                {
+                    back -= 1;
+                }
+                if back == front {
+                    break; // replaced all possibilities. Rest of the free space is in the end
                 }
 
-                compacted[next_dot_index] = Some(*number_from_behind);
-                compacted[original_index] = None;
+                let file = blocks[back - 1];
+                let moved = free.min(file.length);
+
+                compacted.push(Block {
+                    id: file.id,
+                    length: moved,
+                });
+                blocks[back - 1].length -= moved;
+                free -= moved;
             }
-        } else {
-            continue;
         }
-    }
-
-    return compacted;
-}
 
-pub fn compact_blockwise(disk_map: &str) -> u64 {
-    let disk_map = parse_disk_map(disk_map);
-    let disk_map = compact(disk_map);
+        let used_length: u64 = compacted.iter().map(|block| block.length).sum();
+        compacted.push(new_free_block(disk_map.length() - used_length));
 
-    let mut sum = 0;
-
-    for (i, n) in disk_map.iter().enumerate() {
-        if let Some(n) = n {
-            sum += i as u64 * *n;
-        }
+        return DiskMap { blocks: compacted };
     }
-
-    return sum;
 }
 
 // ###############################################################################################################
@@ -68,54 +60,51 @@ pub fn compact_blockwise(disk_map: &str) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{compact, compact_blockwise, parse_disk_map};
-
-    fn render_number_options(numbers: Vec<Option<u64>>) -> String {
-        return numbers
-            .iter()
-            .map(|n| {
-                if let Some(number) = n {
-                    return number.to_string();
-                } else {
-                    return ".".to_string();
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("");
-    }
+    use super::BlockWise;
+    use crate::disk_map::{new_file, new_free_block, Compactor, DiskMap};
 
-    #[test]
-    fn test_parse_disk_map() {
-        let compact_format = "2333133121414131402";
-        let result = parse_disk_map(compact_format);
-        assert_eq!(
-            render_number_options(result),
-            "00...111...2...333.44.5555.6666.777.888899".to_string()
-        );
+    fn compact(compact_format: &str) -> DiskMap {
+        return BlockWise.compact(&DiskMap::parse(compact_format));
     }
 
     #[test]
     fn test_compaction() {
-        let compact_format = "12345";
-        let disk_layout = parse_disk_map(compact_format);
-        let result = compact(disk_layout);
-
-        assert_eq!(render_number_options(result), "022111222......".to_string());
-
-        let compact_format = "2333133121414131402";
-        let disk_layout = parse_disk_map(compact_format);
-        let result = compact(disk_layout);
+        let result = compact("12345");
+        assert_eq!(result.render(), "022111222......".to_string());
 
+        let result = compact("2333133121414131402");
         assert_eq!(
-            render_number_options(result),
+            result.render(),
             "0099811188827773336446555566..............".to_string()
         );
     }
 
     #[test]
     fn test_compact_blockwise() {
-        let compact_format = "2333133121414131402";
-        let result = compact_blockwise(compact_format);
+        let result = compact("2333133121414131402").checksum();
         assert_eq!(result, 1928);
     }
+
+    #[test]
+    fn test_compact_spans() {
+        // only a part of the last file fits into the free space, the rest stays where it is
+        let result = compact("1234");
+        assert_eq!(
+            result.blocks,
+            vec![
+                new_file(0, 1),
+                new_file(1, 2),
+                new_file(1, 1),
+                new_free_block(6)
+            ]
+        );
+
+        // empty free spans are skipped
+        let result = compact("20312");
+        assert_eq!(result.render(), "0011122.");
+
+        // nothing to move without free space
+        let result = compact("30201");
+        assert_eq!(result.render(), "000112");
+    }
 }
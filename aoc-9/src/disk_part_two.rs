@@ -1,132 +1,115 @@
-#[derive(Clone, Copy, Debug)]
-struct Block {
-    id: Option<u64>,
-    length: u64,
-    is_file: bool,
-    freed: bool,
-    copied: bool,
-}
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
-fn new_free_block(length: u64) -> Block {
-    Block {
-        id: None,
-        length,
-        is_file: false,
-        freed: false,
-        copied: false,
-    }
+use crate::disk_map::{Block, Compactor, DiskMap};
+
+// a single digit of the disk map is the longest a file or free space can be
+const MAX_LENGTH: usize = 9;
+
+// The start positions of all free spaces, one min-heap for every length. The leftmost free
+// space a file fits into is the smallest of the tops of the heaps at least as long as the file.
+struct FreeSpace {
+    starts_by_length: Vec<BinaryHeap<Reverse<u64>>>,
 }
 
-fn new_file(id: u64, length: u64) -> Block {
-    Block {
-        id: Some(id),
-        length,
-        is_file: true,
-        freed: false,
-        copied: false,
+impl FreeSpace {
+    fn new() -> FreeSpace {
+        return FreeSpace {
+            starts_by_length: vec![BinaryHeap::new(); MAX_LENGTH + 1],
+        };
     }
-}
 
-fn even(num: u8) -> bool {
-    num % 2 == 0
-}
+    fn add(&mut self, start: u64, length: u64) {
+        if length > 0 {
+            self.starts_by_length[length as usize].push(Reverse(start));
+        }
+    }
 
-fn parse_disk_map(disk_map: &str) -> Vec<Block> {
-    let mut id: u64 = 0;
+    fn leftmost_start(&self, length: usize) -> Option<u64> {
+        let Reverse(start) = self.starts_by_length[length].peek()?;
+        return Some(*start);
+    }
 
-    let mut parsed: Vec<Block> = vec![];
+    fn take(&mut self, length: usize) -> (u64, u64) {
+        let Reverse(start) = self.starts_by_length[length].pop().unwrap();
+        return (start, length as u64);
+    }
 
-    for (index, number) in disk_map.trim().chars().into_iter().enumerate() {
-        let length = number.to_string().parse::<u64>().unwrap();
+    // takes the leftmost free space of at least the given length, returning its start and length
+    fn take_leftmost(&mut self, min_length: u64, before: u64) -> Option<(u64, u64)> {
+        let (start, length) = (min_length as usize..=MAX_LENGTH)
+            .filter_map(|length| Some((self.leftmost_start(length)?, length)))
+            .min()?;
 
-        if even(index as u8) {
-            parsed.push(new_file(id, length));
-            id += 1;
-        } else {
-            parsed.push(new_free_block(length));
+        if start >= before {
+            return None;
         }
+        return Some(self.take(length));
     }
 
-    return parsed;
-}
+    // takes the shortest free space of at least the given length, the leftmost of equally long ones
+    fn take_shortest(&mut self, min_length: u64, before: u64) -> Option<(u64, u64)> {
+        let length = (min_length as usize..=MAX_LENGTH).find(|length| {
+            return self
+                .leftmost_start(*length)
+                .is_some_and(|start| start < before);
+        })?;
 
-fn files_in_reverse_order(disk_map: &Vec<Block>) -> Vec<Block> {
-    disk_map
-        .clone()
-        .iter()
-        .filter(|block| block.is_file)
-        .rev()
-        .map(|b| *b)
-        .collect()
+        return Some(self.take(length));
+    }
 }
 
-fn find_free_block(blocks: &Vec<Block>, min_length: u64) -> Option<(usize, Block)> {
-    if let Some((idx, free_slot)) = blocks
-        .iter()
-        .enumerate()
-        .find(|(_, block)| !block.is_file && block.length >= min_length)
-    {
-        return Some((idx, *free_slot));
+// Moves every file once, from the highest id to the lowest, into a free space it fits into.
+// A file only moves to the left, so the space it frees can't be used by any later file.
+fn move_files(
+    disk_map: &DiskMap,
+    take_free_space: fn(&mut FreeSpace, u64, u64) -> Option<(u64, u64)>,
+) -> DiskMap {
+    let mut files: Vec<(u64, Block)> = vec![];
+    let mut free_space = FreeSpace::new();
+    let mut disk_length = 0;
+
+    for block in &disk_map.blocks {
+        if block.is_file() {
+            files.push((disk_length, *block));
+        } else {
+            free_space.add(disk_length, block.length);
+        }
+        disk_length += block.length;
     }
 
-    return None;
-}
-
-fn defragment(disk_map: Vec<Block>) -> Vec<Block> {
-    let mut defragmented = disk_map.clone();
-
-    for candidate in files_in_reverse_order(&disk_map) {
-        if let Some((slot_index, free_slot)) = find_free_block(&defragmented, candidate.length) {
-            // find current index and get mutable reference of file (after each mutation)
-            if let Some((idx, file)) = defragmented
-                .iter_mut()
-                .enumerate()
-                .find(|(_, f)| f.id == candidate.id)
-            {
-                if slot_index >= idx {
-                    continue;
-                }
-
-                let mut original_file = file.clone();
-                original_file.copied = true;
-
-                file.id = None;
-                file.freed = true;
-
-                // add a free block after replacing the free slot with the file in the length of the remaining part.
-                if free_slot.length > file.length {
-                    let length = free_slot.length - file.length;
-                    defragmented.insert(slot_index + 1, new_free_block(length));
-                }
-
-                defragmented[slot_index] = original_file;
-            }
+    for (start, file) in files.iter_mut().rev() {
+        if file.length == 0 {
+            continue; // an empty file takes no space, so it stays where it is
+        }
+        if let Some((free_start, free_length)) =
+            take_free_space(&mut free_space, file.length, *start)
+        {
+            *start = free_start;
+            free_space.add(free_start + file.length, free_length - file.length);
         }
     }
 
-    return defragmented;
+    return DiskMap::from_files(files, disk_length);
 }
 
-fn checksum(disk_map: Vec<Block>) -> u64 {
-    let mut sum = 0;
-    let mut index = 0;
-
-    for block in disk_map.iter() {
-        let id = block.id.unwrap_or(0);
+// Moves whole files into the leftmost free space they fit into.
+pub struct FileWise;
 
-        for _ in 0..block.length {
-            sum += index * id;
-            index += 1;
-        }
+impl Compactor for FileWise {
+    fn compact(&self, disk_map: &DiskMap) -> DiskMap {
+        return move_files(disk_map, FreeSpace::take_leftmost);
     }
-    return sum;
 }
 
-pub fn defragment_by_file(disk_map: &str) -> u64 {
-    let disk_map = parse_disk_map(disk_map);
-    let disk_map = defragment(disk_map);
-    // println!("checked:\n\n{}", render_blocks(&disk_map));
-    return checksum(disk_map);
+// Moves whole files into the shortest free space they fit into, leaving the longer free spaces
+// for the longer files.
+pub struct BestFit;
+
+impl Compactor for BestFit {
+    fn compact(&self, disk_map: &DiskMap) -> DiskMap {
+        return move_files(disk_map, FreeSpace::take_shortest);
+    }
 }
 
 // ###############################################################################################################
@@ -138,79 +121,90 @@ pub fn defragment_by_file(disk_map: &str) -> u64 {
 #[cfg(test)]
 mod tests {
 
-    use super::{checksum, defragment, defragment_by_file, parse_disk_map, Block};
-
-    // for ids > 9 the rendered ids take more place, this is basically wrong
-    // and we use that only to be able to write down the expectation as string
-    fn render_blocks(blocks: &Vec<Block>) -> String {
-        return blocks
-            .iter()
-            .map(|block| {
-                if let Some(id) = block.id {
-                    return vec![id.to_string(); block.length as usize].join("");
-                } else {
-                    return vec!["."; block.length as usize].join("");
-                }
-            })
-            .collect::<Vec<String>>()
-            .join("");
-    }
+    use super::{BestFit, FileWise, FreeSpace};
+    use crate::disk_map::{Compactor, DiskMap};
 
-    #[test]
-    fn test_parsing() {
-        let compact_format = "2333133121414131402";
-        let result = parse_disk_map(compact_format);
-        assert_eq!(
-            render_blocks(&result),
-            "00...111...2...333.44.5555.6666.777.888899"
-        );
+    fn defragment(compact_format: &str) -> DiskMap {
+        return FileWise.compact(&DiskMap::parse(compact_format));
     }
 
     #[test]
     fn test_compacting() {
-        let compact_format = "2333133121414131402";
-        let disk_layout = parse_disk_map(compact_format);
-        let compacted = defragment(disk_layout);
+        let compacted = defragment("2333133121414131402");
         assert_eq!(
-            render_blocks(&compacted),
+            compacted.render(),
             "00992111777.44.333....5555.6666.....8888.."
         );
 
-        let compact_format = "11111";
-        let disk_layout = parse_disk_map(compact_format);
-        let compacted = defragment(disk_layout);
-        assert_eq!(render_blocks(&compacted), "021..");
+        let compacted = defragment("11111");
+        assert_eq!(compacted.render(), "021..");
 
-        let compact_format = "111111111111111111111111111";
-        let disk_layout = parse_disk_map(compact_format);
-        let compacted = defragment(disk_layout);
-        assert_eq!(render_blocks(&compacted), "013112211310495867.............");
+        let compacted = defragment("111111111111111111111111111");
+        assert_eq!(compacted.render(), "013112211310495867.............");
     }
 
     #[test]
     fn test_checksum() {
-        let compact_format = "11111";
-        let disk_layout = parse_disk_map(compact_format);
-        let compacted = defragment(disk_layout);
-        let result = checksum(compacted);
-        assert_eq!(result, 4);
+        let compacted = defragment("11111");
+        assert_eq!(compacted.checksum(), 4);
 
-        let compact_format = "999";
-        let disk_layout = parse_disk_map(compact_format);
-        let compacted = defragment(disk_layout);
-
-        let result = checksum(compacted);
-        assert_eq!(result, 117);
+        let compacted = defragment("999");
+        assert_eq!(compacted.checksum(), 117);
     }
 
     #[test]
     fn test_compact_by_file() {
-        let compact_format = "2333133121414131402";
-        let result = defragment_by_file(compact_format);
+        let result = defragment("2333133121414131402").checksum();
         assert_eq!(result, 2858);
 
-        let compact_format = "233313312141413140219";
-        let result = defragment_by_file(compact_format);
+        let result = defragment("233313312141413140219").checksum();
         assert_eq!(result, 7088);
     }
+
+    #[test]
+    fn test_empty_files() {
+        // the empty file 2 neither moves nor shifts the files behind it
+        let compacted = defragment("1211013");
+        assert_eq!(compacted.checksum(), 64);
+        assert_eq!(compacted.render(), "01....333");
+        assert_eq!(compacted.length(), 9);
+    }
+
+    #[test]
+    fn test_free_space() {
+        let mut free_space = FreeSpace::new();
+        free_space.add(10, 2);
+        free_space.add(3, 1);
+        free_space.add(20, 5);
+        free_space.add(30, 0);
+
+        // the leftmost one that is long enough, not the shortest one
+        assert_eq!(free_space.take_leftmost(2, 100), Some((10, 2)));
+        assert_eq!(free_space.take_leftmost(2, 100), Some((20, 5)));
+        // files never move to the right
+        assert_eq!(free_space.take_leftmost(1, 3), None);
+        assert_eq!(free_space.take_leftmost(1, 4), Some((3, 1)));
+        assert_eq!(free_space.take_leftmost(1, 100), None);
+
+        let mut free_space = FreeSpace::new();
+        free_space.add(2, 4);
+        free_space.add(10, 2);
+        free_space.add(20, 2);
+        free_space.add(30, 1);
+
+        assert_eq!(free_space.take_shortest(2, 100), Some((10, 2)));
+        // the shorter one is behind the file
+        assert_eq!(free_space.take_shortest(2, 15), Some((2, 4)));
+        assert_eq!(free_space.take_shortest(1, 25), Some((20, 2)));
+    }
+
+    #[test]
+    fn test_best_fit() {
+        // the file 3 leaves the long free space at the front to the file 2
+        let compacted = BestFit.compact(&DiskMap::parse("1311301"));
+        assert_eq!(compacted.render(), "022213....");
+
+        let compacted = FileWise.compact(&DiskMap::parse("1311301"));
+        assert_eq!(compacted.render(), "031...222.");
+    }
 }
@@ -1,18 +1,34 @@
+use std::env;
 use std::fs;
 
-// use disk_part_one::compact_blockwise;
-use disk_part_two::defragment_by_file;
+use disk_map::{Compactor, DiskMap};
+use disk_part_one::BlockWise;
+use disk_part_two::{BestFit, FileWise};
 
+mod disk_map;
 mod disk_part_one;
 mod disk_part_two;
 
 fn main() {
     let disk_map =
         fs::read_to_string("./data/disk_map.txt").expect("Should have been able to read the file");
+    let args: Vec<String> = env::args().collect();
 
-    // let result = compact_blockwise(disk_map.as_str());
-    // println!("checksum: {}", result);
+    let disk_map = DiskMap::parse(disk_map.as_str());
 
-    let result = defragment_by_file(disk_map.as_str());
-    println!("checksum: {}", result);
+    let compactors: [(&str, &dyn Compactor); 3] = [
+        ("block-wise", &BlockWise),
+        ("file-wise", &FileWise),
+        ("best-fit", &BestFit),
+    ];
+
+    for (name, compactor) in compactors {
+        let compacted = compactor.compact(&disk_map);
+        println!("checksum ({}): {}", name, compacted.checksum());
+
+        // `cargo run -- --render` shows the compacted disk, which only looks right for ids < 10
+        if args.iter().any(|arg| arg == "--render") {
+            println!("{}", compacted.render());
+        }
+    }
 }